@@ -0,0 +1,322 @@
+use qsc::{
+    LanguageFeatures, PackageType, SourceMap,
+    ast::{ExprKind, Lit, Pat, PatKind, QubitInit, QubitInitKind, Stmt, StmtKind, visit::{self, Visitor}},
+    interpret::{CircuitEntryPoint, Interpreter}, target::Profile,
+};
+use qsc_circuit::{CURRENT_VERSION, Circuit, CircuitGroup, ComponentGrid, Operation, Register, TracerConfig};
+use std::collections::HashMap;
+
+use crate::sim::QsError;
+
+// Name of the source file the circuits are traced from, as reported in the qubit declarations.
+const SOURCE_NAME: &str = "test.qs";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CircuitGenerationMethod {
+    /// Evaluates the program classically, without simulating the qubits.
+    #[default]
+    ClassicalEval,
+    /// Traces a simulated run, following measurement-dependent control flow.
+    Simulate,
+}
+
+impl From<CircuitGenerationMethod> for qsc::interpret::CircuitGenerationMethod {
+    fn from(method: CircuitGenerationMethod) -> Self {
+        match method {
+            CircuitGenerationMethod::ClassicalEval => qsc::interpret::CircuitGenerationMethod::ClassicalEval,
+            CircuitGenerationMethod::Simulate => qsc::interpret::CircuitGenerationMethod::Simulate,
+        }
+    }
+}
+
+/// How a circuit is traced, shared by the options of all the circuit renderers.
+#[derive(Clone, Debug, Default)]
+pub struct CircuitGenerationOptions {
+    pub group_by_scope: bool,
+    /// Defaults to classical evaluation.
+    pub generation_method: Option<CircuitGenerationMethod>,
+    pub max_operations: Option<u64>,
+    /// Records where qubits are declared, which variable labels need. Defaults to the tracer default.
+    pub source_locations: Option<bool>,
+    /// Number of group levels kept when grouping by scope, at least 1; deeper groups are collapsed into
+    /// boxes, which keep the result wires their measurements wrote.
+    pub max_depth: Option<u32>,
+}
+
+impl CircuitGenerationOptions {
+    // the remaining tracer settings keep their defaults, whatever fields the tracer adds
+    #[allow(clippy::needless_update)]
+    fn tracer_config(&self) -> TracerConfig {
+        let defaults = TracerConfig::default();
+        TracerConfig {
+            group_by_scope: self.group_by_scope,
+            max_operations: self.max_operations.map_or(defaults.max_operations, |max| max as usize),
+            source_locations: self.source_locations.unwrap_or(defaults.source_locations),
+            ..defaults
+        }
+    }
+}
+
+pub fn circuit_json(source: &str, options: CircuitGenerationOptions) -> Result<String, QsError> {
+    let circuit = generate_circuit(source, CircuitEntryPoint::EntryPoint, &options)?;
+    circuit_to_json(&circuit)
+}
+
+pub fn circuit_json_operation(operation: &str, source: &str, options: CircuitGenerationOptions) -> Result<String, QsError> {
+    let circuit = generate_circuit(
+        source,
+        CircuitEntryPoint::Operation(operation.to_string()),
+        &options,
+    )?;
+    circuit_to_json(&circuit)
+}
+
+pub fn circuit_to_json(c: &Circuit) -> Result<String, QsError> {
+    // a circuit file (`.qsc`) of the QDK circuit viewers
+    let group = CircuitGroup {
+        circuits: vec![c.clone()],
+        version: CURRENT_VERSION,
+    };
+
+    serde_json::to_string(&group).map_err(|error| QsError::ErrorMessage {
+        error_text: error.to_string(),
+    })
+}
+
+pub(crate) fn generate_circuit(
+    source: &str,
+    entry_point: CircuitEntryPoint,
+    settings: &CircuitGenerationOptions,
+) -> Result<Circuit, QsError> {
+    if settings.max_depth == Some(0) {
+        return Err(QsError::InvalidParameter {
+            parameter: "max_depth".to_string(),
+            reason: "at least one group level must be kept".to_string(),
+        });
+    }
+
+    let sources = SourceMap::new([(SOURCE_NAME.into(), source.into())], None);
+    let (std_id, store) = qsc::compile::package_store_with_stdlib(Profile::Unrestricted.into());
+
+    let mut interpreter = match Interpreter::with_circuit_trace(
+        sources,
+        PackageType::Exe,
+        Profile::Unrestricted.into(),
+        LanguageFeatures::default(),
+        store,
+        &[(std_id, None)],
+        Default::default(),
+    ) {
+        Ok(interpreter) => interpreter,
+        Err(errors) => {
+            return Err(errors.into());
+        }
+    };
+
+    let mut circuit = interpreter.circuit(
+        entry_point,
+        settings.generation_method.unwrap_or_default().into(),
+        settings.tracer_config(),
+    )?;
+
+    if let Some(max_depth) = settings.max_depth {
+        collapse_groups(&mut circuit, max_depth as usize);
+    }
+
+    Ok(circuit)
+}
+
+// Drops the children of groups nested deeper than `max_depth`, so that they are drawn as single boxes.
+// The wrapper around the whole circuit is not counted, mirroring `top_level_columns`.
+fn collapse_groups(c: &mut Circuit, max_depth: usize) {
+    let grid = &mut c.component_grid;
+    let is_wrapped = grid.len() == 1
+        && grid[0].components.len() == 1
+        && match &grid[0].components[0] {
+            Operation::Unitary(u) => !u.children.is_empty(),
+            Operation::Measurement(m) => !m.children.is_empty(),
+            _ => false,
+        };
+
+    if is_wrapped {
+        collapse_grid(children_mut(&mut grid[0].components[0]), max_depth);
+    } else {
+        collapse_grid(grid, max_depth);
+    }
+}
+
+fn collapse_grid(grid: &mut ComponentGrid, depth_left: usize) {
+    for op in grid.iter_mut().flat_map(|column| column.components.iter_mut()) {
+        if depth_left <= 1 {
+            collapse(op);
+        } else {
+            collapse_grid(children_mut(op), depth_left - 1);
+        }
+    }
+}
+
+// The box of a collapsed group also spans the result wires written by the measurements inside it.
+fn collapse(op: &mut Operation) {
+    let mut results = Vec::new();
+    collect_results(children_mut(op), &mut results);
+    children_mut(op).clear();
+    if let Operation::Unitary(u) = op {
+        for result in results {
+            if !u.targets.contains(&result) {
+                u.targets.push(result);
+            }
+        }
+    }
+}
+
+fn collect_results(grid: &ComponentGrid, results: &mut Vec<Register>) {
+    for op in grid.iter().flat_map(|column| column.components.iter()) {
+        match op {
+            Operation::Measurement(m) => {
+                results.extend(m.results.iter().cloned());
+                collect_results(&m.children, results);
+            }
+            Operation::Unitary(u) => collect_results(&u.children, results),
+            Operation::Ket(k) => collect_results(&k.children, results),
+        }
+    }
+}
+
+fn children_mut(op: &mut Operation) -> &mut ComponentGrid {
+    match op {
+        Operation::Unitary(u) => &mut u.children,
+        Operation::Measurement(m) => &mut m.children,
+        Operation::Ket(k) => &mut k.children,
+    }
+}
+
+pub(crate) fn top_level_columns(c: &Circuit) -> Vec<Vec<&Operation>> {
+    let grid = &c.component_grid;
+
+    // Check if we have a single top-level operation that wraps the entire circuit.
+    // If so, we unwrap it and use its children grid.
+    let maybe_children = if grid.len() == 1 && grid[0].components.len() == 1 {
+        match &grid[0].components[0] {
+            Operation::Unitary(u) => Some(&u.children),
+            Operation::Measurement(m) => Some(&m.children),
+            _ => None,
+        }
+    } else {
+        None
+    };
+
+    maybe_children
+        .filter(|children| !children.is_empty())
+        .map(|children| children.iter().map(|c| c.components.iter().collect()).collect())
+        .unwrap_or_else(|| grid.iter().map(|c| c.components.iter().collect()).collect())
+}
+
+// The Q# variable names of the qubits, e.g. `control`, `target` or `register[3]`, taken from the
+// `use` and `borrow` statements of the parsed source that the tracer reports as their declarations.
+// Qubits are left out when this is ambiguous: without a single declaration in the source, or when
+// the statement allocated more qubits than it names, e.g. in a loop or for a register of computed size.
+pub(crate) fn qubit_variable_names(c: &Circuit, source: &str) -> HashMap<usize, String> {
+    let (namespaces, _) = qsc_parse::namespaces(source, Some(SOURCE_NAME), LanguageFeatures::default());
+    let mut statements = QubitStatements::default();
+    for namespace in &namespaces {
+        statements.visit_namespace(namespace);
+    }
+
+    let mut by_statement: HashMap<usize, Vec<usize>> = HashMap::new();
+    for q in &c.qubits {
+        let [declaration] = q.declarations.as_slice() else {
+            continue;
+        };
+        if declaration.file != SOURCE_NAME {
+            continue;
+        }
+        let Some(offset) = byte_offset(source, declaration.line, declaration.column) else {
+            continue;
+        };
+        // statements with a block contain the statements allocating in it
+        let innermost = statements
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(_, statement)| statement.lo <= offset && offset < statement.hi)
+            .min_by_key(|(_, statement)| statement.hi - statement.lo);
+        if let Some((idx, _)) = innermost {
+            by_statement.entry(idx).or_default().push(q.id);
+        }
+    }
+
+    let mut names = HashMap::new();
+    for (idx, mut ids) in by_statement {
+        let Some(declared) = &statements.0[idx].names else {
+            continue;
+        };
+        if declared.len() != ids.len() {
+            continue;
+        }
+        // qubits are allocated in declaration order
+        ids.sort();
+        names.extend(ids.into_iter().zip(declared.iter().cloned()));
+    }
+    names
+}
+
+struct QubitStatement {
+    lo: u32,
+    hi: u32,
+    names: Option<Vec<String>>,
+}
+
+#[derive(Default)]
+struct QubitStatements(Vec<QubitStatement>);
+
+impl<'a> Visitor<'a> for QubitStatements {
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        if let StmtKind::Qubit(_, pat, init, _) = &*stmt.kind {
+            self.0.push(QubitStatement {
+                lo: stmt.span.lo,
+                hi: stmt.span.hi,
+                names: declared_names(pat, init),
+            });
+        }
+        visit::walk_stmt(self, stmt);
+    }
+}
+
+// Expands the names of the qubits a pattern binds, in allocation order.
+// Registers need a literal size, and every qubit a name.
+fn declared_names(pat: &Pat, init: &QubitInit) -> Option<Vec<String>> {
+    match (&*pat.kind, &*init.kind) {
+        (PatKind::Paren(pat), _) => declared_names(pat, init),
+        (_, QubitInitKind::Paren(init)) => declared_names(pat, init),
+        (PatKind::Bind(ident, _), QubitInitKind::Single) => Some(vec![ident.name.to_string()]),
+        (PatKind::Bind(ident, _), QubitInitKind::Array(size)) => match &*size.kind {
+            ExprKind::Lit(lit) => match &**lit {
+                Lit::Int(size) => Some((0..*size).map(|i| format!("{}[{i}]", ident.name)).collect()),
+                _ => None,
+            },
+            _ => None,
+        },
+        (PatKind::Tuple(pats), QubitInitKind::Tuple(inits)) if pats.len() == inits.len() => {
+            let mut names = Vec::new();
+            for (pat, init) in pats.iter().zip(inits.iter()) {
+                names.extend(declared_names(pat, init)?);
+            }
+            Some(names)
+        }
+        _ => None,
+    }
+}
+
+// The tracer reports zero-based lines and character columns.
+fn byte_offset(source: &str, line: u32, column: u32) -> Option<u32> {
+    let mut start = 0;
+    for _ in 0..line {
+        start += source[start..].find('\n')? + 1;
+    }
+    let line = source[start..].lines().next().unwrap_or_default();
+    let column = line
+        .char_indices()
+        .nth(column as usize)
+        .map_or(line.len(), |(idx, _)| idx);
+    u32::try_from(start + column).ok()
+}
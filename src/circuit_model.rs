@@ -0,0 +1,132 @@
+use qsc::interpret::CircuitEntryPoint;
+use qsc_circuit::{ComponentColumn, Operation, Register};
+
+use crate::circuit::{generate_circuit, CircuitGenerationOptions};
+use crate::sim::QsError;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Circuit {
+    pub qubits: Vec<CircuitQubit>,
+    pub columns: Vec<CircuitColumn>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CircuitQubit {
+    pub id: u64,
+    pub num_results: u64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CircuitColumn {
+    pub operations: Vec<CircuitOperation>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CircuitOperation {
+    Unitary {
+        gate: String,
+        is_adjoint: bool,
+        args: Vec<String>,
+        controls: Vec<CircuitRegister>,
+        targets: Vec<CircuitRegister>,
+        children: Vec<CircuitColumn>,
+    },
+    Measurement {
+        gate: String,
+        args: Vec<String>,
+        qubits: Vec<CircuitRegister>,
+        results: Vec<CircuitRegister>,
+        children: Vec<CircuitColumn>,
+    },
+    Ket {
+        gate: String,
+        args: Vec<String>,
+        targets: Vec<CircuitRegister>,
+        children: Vec<CircuitColumn>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CircuitRegister {
+    pub qubit: u64,
+    pub result: Option<u64>,
+}
+
+pub fn circuit(source: &str, options: CircuitGenerationOptions) -> Result<Circuit, QsError> {
+    let circuit = generate_circuit(source, CircuitEntryPoint::EntryPoint, &options)?;
+    Ok((&circuit).into())
+}
+
+pub fn circuit_operation(operation: &str, source: &str, options: CircuitGenerationOptions) -> Result<Circuit, QsError> {
+    let circuit = generate_circuit(
+        source,
+        CircuitEntryPoint::Operation(operation.to_string()),
+        &options,
+    )?;
+    Ok((&circuit).into())
+}
+
+impl From<&qsc_circuit::Circuit> for Circuit {
+    fn from(c: &qsc_circuit::Circuit) -> Self {
+        Self {
+            qubits: c
+                .qubits
+                .iter()
+                .map(|q| CircuitQubit {
+                    id: q.id as u64,
+                    num_results: q.num_results as u64,
+                })
+                .collect(),
+            columns: columns(&c.component_grid),
+        }
+    }
+}
+
+impl From<&Operation> for CircuitOperation {
+    fn from(op: &Operation) -> Self {
+        match op {
+            Operation::Unitary(u) => CircuitOperation::Unitary {
+                gate: u.gate.clone(),
+                is_adjoint: u.is_adjoint,
+                args: u.args.clone(),
+                controls: registers(&u.controls),
+                targets: registers(&u.targets),
+                children: columns(&u.children),
+            },
+            Operation::Measurement(m) => CircuitOperation::Measurement {
+                gate: m.gate.clone(),
+                args: m.args.clone(),
+                qubits: registers(&m.qubits),
+                results: registers(&m.results),
+                children: columns(&m.children),
+            },
+            Operation::Ket(k) => CircuitOperation::Ket {
+                gate: k.gate.clone(),
+                args: k.args.clone(),
+                targets: registers(&k.targets),
+                children: columns(&k.children),
+            },
+        }
+    }
+}
+
+impl From<&Register> for CircuitRegister {
+    fn from(reg: &Register) -> Self {
+        Self {
+            qubit: reg.qubit as u64,
+            result: reg.result.map(|r| r as u64),
+        }
+    }
+}
+
+fn columns(grid: &[ComponentColumn]) -> Vec<CircuitColumn> {
+    grid.iter()
+        .map(|col| CircuitColumn {
+            operations: col.components.iter().map(CircuitOperation::from).collect(),
+        })
+        .collect()
+}
+
+fn registers(registers: &[Register]) -> Vec<CircuitRegister> {
+    registers.iter().map(CircuitRegister::from).collect()
+}
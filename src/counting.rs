@@ -0,0 +1,434 @@
+use std::collections::{HashMap, HashSet};
+
+use resource_estimator::estimate_entry;
+
+use crate::estimation_result::{LogicalCounts, parse_estimation_results};
+use crate::sim::{QsError, create_qasm_interpreter, estimate_logical_counts};
+
+/// Estimates an OpenQASM 2.0 or 3.0 program, compiled with the OpenQASM front end of the QDK.
+pub fn estimate_qasm(source: &str, job_params: Option<String>) -> Result<String, QsError> {
+    let mut interpreter = create_qasm_interpreter(source)?;
+    let params = job_params.as_deref().unwrap_or("[{}]");
+    Ok(estimate_entry(&mut interpreter, params)?)
+}
+
+pub fn estimate_qir(ir: &str, job_params: Option<String>) -> Result<String, QsError> {
+    estimate_logical_counts(qir_logical_counts(ir)?, job_params)
+}
+
+/// Counts the logical resources of an OpenQASM program, as reported by the resource estimator.
+pub fn qasm_logical_counts(source: &str) -> Result<LogicalCounts, QsError> {
+    let results = parse_estimation_results(&estimate_qasm(source, None)?)?;
+    Ok(results.into_iter().next().map(|result| result.logical_counts).unwrap_or_default())
+}
+
+// Tracks the logical resources the same way the Q# resource estimator classifies them:
+// rotations by multiples of π/2 are Clifford, odd multiples of π/4 are T gates,
+// Toffoli-like gates are CCZ, and everything else is an arbitrary rotation.
+#[derive(Clone, Default)]
+struct LogicalCounter {
+    counts: LogicalCounts,
+    // per-qubit number of rotation layers
+    layers: Vec<u64>,
+}
+
+impl LogicalCounter {
+    fn ensure_qubits(&mut self, count: usize) {
+        if self.layers.len() < count {
+            self.layers.resize(count, 0);
+        }
+        self.counts.num_qubits = self.counts.num_qubits.max(count as u64);
+    }
+
+    fn clifford(&mut self, qubits: &[usize]) {
+        self.synchronize(qubits);
+    }
+
+    fn t(&mut self, qubit: usize) {
+        self.ensure_qubits(qubit + 1);
+        self.counts.t_count += 1;
+    }
+
+    fn ccz(&mut self, qubits: &[usize]) {
+        self.synchronize(qubits);
+        self.counts.ccz_count += 1;
+    }
+
+    fn measure(&mut self, qubit: usize) {
+        self.ensure_qubits(qubit + 1);
+        self.counts.measurement_count += 1;
+    }
+
+    fn rotation(&mut self, qubit: usize, angle: Option<f64>) {
+        self.ensure_qubits(qubit + 1);
+        if let Some(angle) = angle {
+            let eighths = angle / (std::f64::consts::PI / 4.0);
+            if (eighths - eighths.round()).abs() < 1e-9 {
+                // multiples of π/2 are Clifford, the remaining multiples of π/4 are T gates
+                if (eighths.round() as i64) % 2 != 0 {
+                    self.counts.t_count += 1;
+                }
+                return;
+            }
+        }
+
+        self.counts.rotation_count += 1;
+        self.layers[qubit] += 1;
+        self.counts.rotation_depth = self.counts.rotation_depth.max(self.layers[qubit]);
+    }
+
+    // multi-qubit gates order the rotations on their qubits
+    fn synchronize(&mut self, qubits: &[usize]) {
+        let Some(&max) = qubits.iter().max() else {
+            return;
+        };
+        self.ensure_qubits(max + 1);
+        let layer = qubits.iter().map(|&q| self.layers[q]).max().unwrap_or(0);
+        for &q in qubits {
+            self.layers[q] = layer;
+        }
+    }
+
+    // Where control flow joins, each count is the larger one of the incoming paths,
+    // so the result is an upper bound for every path through the program.
+    fn merge(&mut self, other: &LogicalCounter) {
+        let (counts, other_counts) = (&mut self.counts, &other.counts);
+        counts.num_qubits = counts.num_qubits.max(other_counts.num_qubits);
+        counts.t_count = counts.t_count.max(other_counts.t_count);
+        counts.rotation_count = counts.rotation_count.max(other_counts.rotation_count);
+        counts.rotation_depth = counts.rotation_depth.max(other_counts.rotation_depth);
+        counts.ccz_count = counts.ccz_count.max(other_counts.ccz_count);
+        counts.ccix_count = counts.ccix_count.max(other_counts.ccix_count);
+        counts.measurement_count = counts.measurement_count.max(other_counts.measurement_count);
+
+        self.ensure_qubits(other.layers.len());
+        for (layer, &other_layer) in self.layers.iter_mut().zip(&other.layers) {
+            *layer = (*layer).max(other_layer);
+        }
+    }
+}
+
+fn unsupported(what: &str) -> QsError {
+    QsError::ErrorMessage {
+        error_text: format!("Unsupported QIR: {what}"),
+    }
+}
+
+/// Counts the logical resources of QIR in LLVM text form, starting at the entry point.
+/// Calls to functions defined in the module are followed. Where the program branches, each
+/// count is the larger one of the branches, which gives an upper bound. Loops are not supported.
+pub fn qir_logical_counts(ir: &str) -> Result<LogicalCounts, QsError> {
+    let module = QirModule::parse(ir)?;
+    let mut counter = LogicalCounter::default();
+    if let Some(required) = module.required_num_qubits {
+        counter.ensure_qubits(required);
+    }
+
+    let counter = module.walk(module.entry_point, &HashMap::new(), counter, &mut Vec::new())?;
+    Ok(counter.counts)
+}
+
+struct QirFunction<'a> {
+    params: Vec<&'a str>,
+    blocks: Vec<QirBlock<'a>>,
+}
+
+struct QirBlock<'a> {
+    label: &'a str,
+    instructions: Vec<&'a str>,
+    successors: Vec<&'a str>,
+}
+
+struct QirModule<'a> {
+    functions: HashMap<&'a str, QirFunction<'a>>,
+    entry_point: &'a str,
+    required_num_qubits: Option<usize>,
+}
+
+impl<'a> QirModule<'a> {
+    fn parse(ir: &'a str) -> Result<Self, QsError> {
+        // `attributes #0 = { "entry_point" "required_num_qubits"="3" }`
+        let mut attributes = HashMap::new();
+        for line in ir.lines().map(str::trim) {
+            if let Some((group, values)) = line.strip_prefix("attributes").and_then(|rest| rest.split_once('=')) {
+                attributes.insert(group.trim(), values.trim());
+            }
+        }
+
+        let mut functions = HashMap::new();
+        let mut entry_point = None;
+        let mut required_num_qubits = None;
+        let mut lines = ir.lines().map(str::trim);
+        while let Some(line) = lines.next() {
+            let Some(header) = line.strip_prefix("define ") else {
+                continue;
+            };
+            let name = callee(header).ok_or_else(|| unsupported(&format!("function `{line}`")))?;
+            let (params, rest) = arguments(header.split_once('(').map_or("", |(_, rest)| rest));
+
+            // the attributes are either inline or in a group like `#0`
+            let function_attributes = rest
+                .split_whitespace()
+                .filter_map(|token| attributes.get(token).copied())
+                .chain([rest])
+                .collect::<Vec<_>>()
+                .join(" ");
+            if function_attributes.contains("\"entry_point\"") {
+                entry_point = Some(name);
+                required_num_qubits = function_attributes
+                    .split("\"required_num_qubits\"=\"")
+                    .nth(1)
+                    .and_then(|rest| rest.split('"').next())
+                    .and_then(|n| n.parse::<usize>().ok());
+            }
+
+            let params = params.iter().filter_map(|param| param.split_whitespace().last()).collect();
+            let blocks = QirModule::parse_blocks(&mut lines)?;
+            functions.insert(name, QirFunction { params, blocks });
+        }
+
+        // without an entry point attribute, a module with a single function is its own entry point
+        let entry_point = match entry_point {
+            Some(entry_point) => entry_point,
+            None if functions.len() == 1 => functions.keys().next().copied().unwrap_or_default(),
+            None => return Err(unsupported("missing entry point")),
+        };
+        Ok(QirModule { functions, entry_point, required_num_qubits })
+    }
+
+    // Reads the body of a function up to its closing brace, splitting it at labels and terminators.
+    fn parse_blocks(lines: &mut impl Iterator<Item = &'a str>) -> Result<Vec<QirBlock<'a>>, QsError> {
+        let mut blocks = vec![QirBlock { label: "", instructions: Vec::new(), successors: Vec::new() }];
+        for line in lines.by_ref() {
+            if line == "}" {
+                return Ok(blocks.into_iter().filter(|b| !b.label.is_empty() || !b.instructions.is_empty()).collect());
+            }
+            let code = line.split(';').next().unwrap_or_default().trim();
+            if code.is_empty() {
+                continue;
+            }
+
+            if let Some(label) = code.strip_suffix(':') {
+                let current = blocks.last_mut().filter(|b| b.label.is_empty() && b.instructions.is_empty());
+                match current {
+                    Some(block) => block.label = label,
+                    None => blocks.push(QirBlock { label, instructions: Vec::new(), successors: Vec::new() }),
+                }
+                continue;
+            }
+
+            let block = blocks.last_mut().expect("a function starts with a block");
+            if code.starts_with("br ") {
+                block.successors = code.split("label %").skip(1).filter_map(|rest| rest.split([',', ' ']).next()).collect();
+            } else if code.starts_with("switch ") || code.starts_with("indirectbr ") {
+                return Err(unsupported(&format!("terminator `{code}`")));
+            } else if !code.starts_with("ret") && code != "unreachable" {
+                block.instructions.push(code);
+            }
+        }
+        Err(unsupported("function without closing brace"))
+    }
+
+    // Walks the blocks of a function in an order where each block comes after its predecessors,
+    // merging the counts of the paths that lead into a block.
+    fn walk(
+        &self,
+        name: &str,
+        bindings: &HashMap<&'a str, &'a str>,
+        counter: LogicalCounter,
+        stack: &mut Vec<&'a str>,
+    ) -> Result<LogicalCounter, QsError> {
+        let function = self.functions.get(name).ok_or_else(|| unsupported(&format!("unknown function `{name}`")))?;
+        let order = block_order(function)?;
+
+        let mut incoming: HashMap<&str, LogicalCounter> = HashMap::new();
+        let mut exit: Option<LogicalCounter> = None;
+        for (idx, &block_idx) in order.iter().enumerate() {
+            let block = &function.blocks[block_idx];
+            let mut state = if idx == 0 {
+                counter.clone()
+            } else {
+                incoming.remove(block.label).unwrap_or_default()
+            };
+
+            for instruction in &block.instructions {
+                self.instruction(instruction, bindings, &mut state, stack)?;
+            }
+
+            if block.successors.is_empty() {
+                match &mut exit {
+                    Some(exit) => exit.merge(&state),
+                    None => exit = Some(state),
+                }
+                continue;
+            }
+            for successor in &block.successors {
+                match incoming.get_mut(successor) {
+                    Some(merged) => merged.merge(&state),
+                    None => {
+                        incoming.insert(*successor, state.clone());
+                    }
+                }
+            }
+        }
+        Ok(exit.unwrap_or(counter))
+    }
+
+    fn instruction(
+        &self,
+        instruction: &'a str,
+        bindings: &HashMap<&'a str, &'a str>,
+        counter: &mut LogicalCounter,
+        stack: &mut Vec<&'a str>,
+    ) -> Result<(), QsError> {
+        let Some((_, call)) = instruction.split_once("call ") else {
+            return Ok(());
+        };
+        let Some(name) = callee(call) else {
+            return Ok(());
+        };
+        let (args, _) = arguments(call.split_once('(').map_or("", |(_, rest)| rest));
+        // arguments of a called function refer to the values of its caller
+        let args: Vec<&str> = args.into_iter().map(|arg| resolve(arg, bindings)).collect();
+
+        if name == "__quantum__rt__qubit_allocate" || name == "__quantum__rt__qubit_allocate_array" {
+            return Err(unsupported("dynamic qubit allocation"));
+        }
+        if let Some(intrinsic) = name.strip_prefix("__quantum__qis__") {
+            return intrinsic_call(intrinsic, &args, counter, instruction);
+        }
+        if let Some(function) = self.functions.get(name) {
+            if stack.contains(&name) {
+                return Err(unsupported(&format!("recursive call of `{name}`")));
+            }
+            let bindings = function.params.iter().copied().zip(args).collect();
+            stack.push(name);
+            *counter = self.walk(name, &bindings, std::mem::take(counter), stack)?;
+            stack.pop();
+        }
+        Ok(())
+    }
+}
+
+// Orders the reachable blocks so that every block follows its predecessors, rejecting loops.
+fn block_order(function: &QirFunction) -> Result<Vec<usize>, QsError> {
+    let index: HashMap<&str, usize> = function.blocks.iter().enumerate().map(|(idx, b)| (b.label, idx)).collect();
+    let mut postorder = Vec::new();
+    let mut visited = HashSet::new();
+    let mut active = HashSet::new();
+
+    fn visit(
+        idx: usize,
+        function: &QirFunction,
+        index: &HashMap<&str, usize>,
+        visited: &mut HashSet<usize>,
+        active: &mut HashSet<usize>,
+        postorder: &mut Vec<usize>,
+    ) -> Result<(), QsError> {
+        if active.contains(&idx) {
+            return Err(unsupported(&format!("loop at block `{}`", function.blocks[idx].label)));
+        }
+        if !visited.insert(idx) {
+            return Ok(());
+        }
+        active.insert(idx);
+        for successor in &function.blocks[idx].successors {
+            let next = *index.get(successor).ok_or_else(|| unsupported(&format!("unknown block `{successor}`")))?;
+            visit(next, function, index, visited, active, postorder)?;
+        }
+        active.remove(&idx);
+        postorder.push(idx);
+        Ok(())
+    }
+
+    if !function.blocks.is_empty() {
+        visit(0, function, &index, &mut visited, &mut active, &mut postorder)?;
+    }
+    postorder.reverse();
+    Ok(postorder)
+}
+
+fn intrinsic_call(name: &str, args: &[&str], counter: &mut LogicalCounter, instruction: &str) -> Result<(), QsError> {
+    let qubit = |idx: usize| -> Result<usize, QsError> {
+        args.get(idx)
+            .and_then(|arg| ir_pointer_index(arg))
+            .ok_or_else(|| unsupported(&format!("qubit argument in `{instruction}`")))
+    };
+    let angle = |idx: usize| args.get(idx).and_then(|arg| ir_double(arg));
+
+    match name {
+        "h__body" | "x__body" | "y__body" | "z__body" | "s__body" | "s__adj" | "sx__body" | "reset__body" => {
+            counter.clifford(&[qubit(0)?])
+        }
+        "t__body" | "t__adj" => counter.t(qubit(0)?),
+        "rx__body" | "ry__body" | "rz__body" => counter.rotation(qubit(1)?, angle(0)),
+        "cnot__body" | "cx__body" | "cy__body" | "cz__body" | "swap__body" => {
+            counter.clifford(&[qubit(0)?, qubit(1)?])
+        }
+        "ccx__body" | "ccz__body" => counter.ccz(&[qubit(0)?, qubit(1)?, qubit(2)?]),
+        "m__body" | "mz__body" | "mresetz__body" => counter.measure(qubit(0)?),
+        "read_result__body" | "barrier__body" => {}
+        _ => return Err(unsupported(&format!("intrinsic `__quantum__qis__{name}`"))),
+    }
+    Ok(())
+}
+
+// The name of the function in `void @name(...)`.
+fn callee(text: &str) -> Option<&str> {
+    let rest = text.split_once('@')?.1;
+    let end = rest.find('(')?;
+    Some(rest[..end].trim_matches('"'))
+}
+
+// Splits the argument list after the opening parenthesis, returning the arguments and the text after the list.
+fn arguments(args: &str) -> (Vec<&str>, &str) {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (idx, ch) in args.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' if depth == 0 => {
+                parts.push(args[start..idx].trim());
+                return (parts.into_iter().filter(|p| !p.is_empty()).collect(), &args[idx + 1..]);
+            }
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(args[start..idx].trim());
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(args[start..].trim());
+    (parts.into_iter().filter(|p| !p.is_empty()).collect(), "")
+}
+
+// `%Qubit* %q` takes the argument the caller passed for the parameter `%q`.
+fn resolve<'a>(arg: &'a str, bindings: &HashMap<&'a str, &'a str>) -> &'a str {
+    arg.split_whitespace()
+        .last()
+        .and_then(|value| bindings.get(value))
+        .copied()
+        .unwrap_or(arg)
+}
+
+// `%Qubit* null`, `ptr null` or `%Qubit* inttoptr (i64 3 to %Qubit*)`
+fn ir_pointer_index(arg: &str) -> Option<usize> {
+    if arg.ends_with("null") {
+        return Some(0);
+    }
+    let rest = arg.split("inttoptr").nth(1)?;
+    let rest = rest.trim_start().trim_start_matches('(').trim_start();
+    let rest = rest.strip_prefix("i64")?.trim_start();
+    rest.split_whitespace().next()?.parse().ok()
+}
+
+// `double 0.5`, `double 5.000000e-01` or `double 0x3FE0000000000000`
+fn ir_double(arg: &str) -> Option<f64> {
+    let value = arg.strip_prefix("double")?.trim();
+    match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok().map(f64::from_bits),
+        None => value.parse().ok(),
+    }
+}
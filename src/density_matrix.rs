@@ -0,0 +1,470 @@
+use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2, FRAC_PI_4};
+
+use num_bigint::BigUint;
+use num_complex::Complex64;
+use qsc::Backend;
+use qsc_eval::val;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::noise::{GateChannels, Matrix2, Noise, TwoQubitNoise};
+use crate::sim::{ExecutionOptions, QsError, SimulatorBackend};
+
+/// The density matrix grows with 4^n, which limits the simulator to small programs.
+pub(crate) const MAX_QUBITS: usize = 10;
+
+enum TwoQubitKraus {
+    Independent(Vec<Matrix2>),
+    Correlated(Vec<([char; 2], f64)>),
+}
+
+fn two_qubit_kraus(noise: &TwoQubitNoise) -> Result<TwoQubitKraus, QsError> {
+    match noise {
+        TwoQubitNoise::Independent { noise } => Ok(TwoQubitKraus::Independent(noise.kraus_operators()?)),
+        correlated => Ok(TwoQubitKraus::Correlated(correlated.to_distribution()?)),
+    }
+}
+
+fn c(re: f64, im: f64) -> Complex64 {
+    Complex64::new(re, im)
+}
+
+fn pauli(pauli: char) -> Matrix2 {
+    match pauli {
+        'X' => [[c(0.0, 0.0), c(1.0, 0.0)], [c(1.0, 0.0), c(0.0, 0.0)]],
+        'Y' => [[c(0.0, 0.0), c(0.0, -1.0)], [c(0.0, 1.0), c(0.0, 0.0)]],
+        'Z' => [[c(1.0, 0.0), c(0.0, 0.0)], [c(0.0, 0.0), c(-1.0, 0.0)]],
+        _ => [[c(1.0, 0.0), c(0.0, 0.0)], [c(0.0, 0.0), c(1.0, 0.0)]],
+    }
+}
+
+fn hadamard() -> Matrix2 {
+    [[c(FRAC_1_SQRT_2, 0.0), c(FRAC_1_SQRT_2, 0.0)], [c(FRAC_1_SQRT_2, 0.0), c(-FRAC_1_SQRT_2, 0.0)]]
+}
+
+fn phase(angle: f64) -> Matrix2 {
+    [[c(1.0, 0.0), c(0.0, 0.0)], [c(0.0, 0.0), Complex64::from_polar(1.0, angle)]]
+}
+
+fn rx(theta: f64) -> Matrix2 {
+    let (sin, cos) = (theta / 2.0).sin_cos();
+    [[c(cos, 0.0), c(0.0, -sin)], [c(0.0, -sin), c(cos, 0.0)]]
+}
+
+fn ry(theta: f64) -> Matrix2 {
+    let (sin, cos) = (theta / 2.0).sin_cos();
+    [[c(cos, 0.0), c(-sin, 0.0)], [c(sin, 0.0), c(cos, 0.0)]]
+}
+
+fn rz(theta: f64) -> Matrix2 {
+    [
+        [Complex64::from_polar(1.0, -theta / 2.0), c(0.0, 0.0)],
+        [c(0.0, 0.0), Complex64::from_polar(1.0, theta / 2.0)],
+    ]
+}
+
+/// The density matrix, row-major. Bit `k` of a basis index belongs to the qubit at position `k`.
+struct State {
+    rho: Vec<Complex64>,
+    dim: usize,
+}
+
+impl State {
+    /// Multiplies the row (or column) index space with `m` on `target`, where all `controls` are set.
+    fn multiply(&mut self, m: &Matrix2, controls: &[usize], target: usize, rows: bool) {
+        let bit = 1 << target;
+        let mask = controls.iter().fold(0, |mask, control| mask | (1 << control));
+        let dim = self.dim;
+        for index in (0..dim).filter(|index| index & bit == 0 && index & mask == mask) {
+            let partner = index | bit;
+            for other in 0..dim {
+                let (i0, i1) = if rows {
+                    (index * dim + other, partner * dim + other)
+                } else {
+                    (other * dim + index, other * dim + partner)
+                };
+                let (a, b) = (self.rho[i0], self.rho[i1]);
+                self.rho[i0] = m[0][0] * a + m[0][1] * b;
+                self.rho[i1] = m[1][0] * a + m[1][1] * b;
+            }
+        }
+    }
+
+    /// rho -> U rho U^dagger
+    fn apply(&mut self, u: &Matrix2, controls: &[usize], target: usize) {
+        let conjugate = u.map(|row| row.map(|v| v.conj()));
+        self.multiply(u, controls, target, true);
+        self.multiply(&conjugate, controls, target, false);
+    }
+
+    /// rho -> sum of K rho K^dagger
+    fn kraus(&mut self, operators: &[Matrix2], target: usize) {
+        if operators.is_empty() {
+            return;
+        }
+        let mut result = vec![c(0.0, 0.0); self.rho.len()];
+        for operator in operators {
+            let mut term = State {
+                rho: self.rho.clone(),
+                dim: self.dim,
+            };
+            term.apply(operator, &[], target);
+            for (sum, value) in result.iter_mut().zip(&term.rho) {
+                *sum += value;
+            }
+        }
+        self.rho = result;
+    }
+
+    fn pauli_pairs(&mut self, errors: &[([char; 2], f64)], q0: usize, q1: usize) {
+        if errors.is_empty() {
+            return;
+        }
+        let p_identity = 1.0 - errors.iter().map(|(_, p)| p).sum::<f64>();
+        let mut result: Vec<Complex64> = self.rho.iter().map(|v| v * p_identity).collect();
+        for &([first, second], p) in errors {
+            let mut term = State {
+                rho: self.rho.clone(),
+                dim: self.dim,
+            };
+            term.apply(&pauli(first), &[], q0);
+            term.apply(&pauli(second), &[], q1);
+            for (sum, value) in result.iter_mut().zip(&term.rho) {
+                *sum += value * p;
+            }
+        }
+        self.rho = result;
+    }
+
+    fn probability_of_one(&self, target: usize) -> f64 {
+        (0..self.dim)
+            .filter(|index| index & (1 << target) != 0)
+            .map(|index| self.rho[index * self.dim + index].re)
+            .sum()
+    }
+
+    fn measure(&mut self, target: usize, rng: &mut StdRng) -> bool {
+        let p_one = self.probability_of_one(target).clamp(0.0, 1.0);
+        let outcome = rng.r#gen::<f64>() < p_one;
+        let p_outcome = if outcome { p_one } else { 1.0 - p_one };
+        let bit = 1 << target;
+        let dim = self.dim;
+        for (index, value) in self.rho.iter_mut().enumerate() {
+            let (row, column) = (index / dim, index % dim);
+            if (row & bit != 0) != outcome || (column & bit != 0) != outcome {
+                *value = c(0.0, 0.0);
+            } else {
+                *value /= p_outcome;
+            }
+        }
+        outcome
+    }
+
+    /// Adds a qubit in the zero state at the highest position.
+    fn expand(&mut self) {
+        let dim = self.dim * 2;
+        let mut rho = vec![c(0.0, 0.0); dim * dim];
+        for row in 0..self.dim {
+            rho[row * dim..row * dim + self.dim].copy_from_slice(&self.rho[row * self.dim..(row + 1) * self.dim]);
+        }
+        self.rho = rho;
+        self.dim = dim;
+    }
+
+    fn trace_out(&mut self, target: usize) {
+        let dim = self.dim / 2;
+        let low = (1 << target) - 1;
+        let insert = |index: usize, bit: usize| ((index & !low) << 1) | (bit << target) | (index & low);
+        let mut rho = vec![c(0.0, 0.0); dim * dim];
+        for row in 0..dim {
+            for column in 0..dim {
+                rho[row * dim + column] = (0..2)
+                    .map(|bit| self.rho[insert(row, bit) * self.dim + insert(column, bit)])
+                    .sum();
+            }
+        }
+        self.rho = rho;
+        self.dim = dim;
+    }
+}
+
+/// Exact simulation of mixed states, for channels that are not Pauli channels. Gate noise is applied as
+/// Kraus channels after each gate, measurement noise before the measurement.
+pub(crate) struct DensityMatrixSim {
+    state: State,
+    /// Qubit ids by position.
+    qubits: Vec<usize>,
+    channels: GateChannels<Vec<Matrix2>, TwoQubitKraus>,
+    rng: StdRng,
+    error: Option<String>,
+}
+
+impl DensityMatrixSim {
+    pub(crate) fn new(options: &ExecutionOptions) -> Result<Self, QsError> {
+        let model = options.noise_model.clone().unwrap_or_default();
+        let channels = model.gate_channels(&options.noise, Noise::kraus_operators, two_qubit_kraus)?;
+        Ok(Self {
+            state: State {
+                rho: vec![c(1.0, 0.0)],
+                dim: 1,
+            },
+            qubits: Vec::new(),
+            channels,
+            rng: StdRng::from_entropy(),
+            error: None,
+        })
+    }
+
+    fn position(&self, q: usize) -> Option<usize> {
+        self.qubits.iter().position(|&id| id == q)
+    }
+
+    fn gate(&mut self, controls: &[usize], q: usize, u: Matrix2, channel: fn(&GateChannels<Vec<Matrix2>, TwoQubitKraus>) -> &Vec<Matrix2>) {
+        let Some(target) = self.position(q) else {
+            return;
+        };
+        let Some(controls) = controls.iter().map(|&control| self.position(control)).collect::<Option<Vec<_>>>() else {
+            return;
+        };
+        self.state.apply(&u, &controls, target);
+        for position in controls.iter().chain([&target]) {
+            self.state.kraus(channel(&self.channels), *position);
+        }
+    }
+
+    fn two_qubit_gate(
+        &mut self,
+        q0: usize,
+        q1: usize,
+        unitary: impl FnOnce(&mut State, usize, usize),
+        channel: fn(&GateChannels<Vec<Matrix2>, TwoQubitKraus>) -> &TwoQubitKraus,
+    ) {
+        let (Some(p0), Some(p1)) = (self.position(q0), self.position(q1)) else {
+            return;
+        };
+        unitary(&mut self.state, p0, p1);
+        match channel(&self.channels) {
+            TwoQubitKraus::Independent(operators) => {
+                self.state.kraus(operators, p0);
+                self.state.kraus(operators, p1);
+            }
+            TwoQubitKraus::Correlated(errors) => self.state.pauli_pairs(errors, p0, p1),
+        }
+    }
+
+    fn measure(&mut self, q: usize, channel: fn(&GateChannels<Vec<Matrix2>, TwoQubitKraus>) -> &Vec<Matrix2>) -> Option<(usize, bool)> {
+        let target = self.position(q)?;
+        self.state.kraus(channel(&self.channels), target);
+        Some((target, self.state.measure(target, &mut self.rng)))
+    }
+}
+
+impl SimulatorBackend for DensityMatrixSim {
+    fn take_error(&mut self) -> Option<String> {
+        self.error.take()
+    }
+}
+
+fn rzz(state: &mut State, theta: f64, p0: usize, p1: usize) {
+    state.apply(&pauli('X'), &[p0], p1);
+    state.apply(&rz(theta), &[], p1);
+    state.apply(&pauli('X'), &[p0], p1);
+}
+
+fn rxx(state: &mut State, theta: f64, p0: usize, p1: usize) {
+    state.apply(&hadamard(), &[], p0);
+    state.apply(&hadamard(), &[], p1);
+    rzz(state, theta, p0, p1);
+    state.apply(&hadamard(), &[], p0);
+    state.apply(&hadamard(), &[], p1);
+}
+
+impl Backend for DensityMatrixSim {
+    fn ccx(&mut self, ctl0: usize, ctl1: usize, q: usize) {
+        // the noise of a CCX is applied to all three qubits
+        self.gate(&[ctl0, ctl1], q, pauli('X'), |channels| &channels.ccx);
+    }
+
+    fn cx(&mut self, ctl: usize, q: usize) {
+        self.two_qubit_gate(ctl, q, |state, p0, p1| state.apply(&pauli('X'), &[p0], p1), |channels| &channels.cx);
+    }
+
+    fn cy(&mut self, ctl: usize, q: usize) {
+        self.two_qubit_gate(ctl, q, |state, p0, p1| state.apply(&pauli('Y'), &[p0], p1), |channels| &channels.cy);
+    }
+
+    fn cz(&mut self, ctl: usize, q: usize) {
+        self.two_qubit_gate(ctl, q, |state, p0, p1| state.apply(&pauli('Z'), &[p0], p1), |channels| &channels.cz);
+    }
+
+    fn h(&mut self, q: usize) {
+        self.gate(&[], q, hadamard(), |channels| &channels.h);
+    }
+
+    fn m(&mut self, q: usize) -> val::Result {
+        let outcome = self.measure(q, |channels| &channels.mz).is_some_and(|(_, outcome)| outcome);
+        val::Result::Val(outcome)
+    }
+
+    fn mresetz(&mut self, q: usize) -> val::Result {
+        let Some((target, outcome)) = self.measure(q, |channels| &channels.mresetz) else {
+            return val::Result::Val(false);
+        };
+        if outcome {
+            self.state.apply(&pauli('X'), &[], target);
+        }
+        val::Result::Val(outcome)
+    }
+
+    fn reset(&mut self, q: usize) {
+        let Some(target) = self.position(q) else {
+            return;
+        };
+        let to_zero = [
+            [[c(1.0, 0.0), c(0.0, 0.0)], [c(0.0, 0.0), c(0.0, 0.0)]],
+            [[c(0.0, 0.0), c(1.0, 0.0)], [c(0.0, 0.0), c(0.0, 0.0)]],
+        ];
+        self.state.kraus(&to_zero, target);
+        self.state.kraus(&self.channels.reset, target);
+    }
+
+    fn rx(&mut self, theta: f64, q: usize) {
+        self.gate(&[], q, rx(theta), |channels| &channels.rx);
+    }
+
+    fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.two_qubit_gate(q0, q1, |state, p0, p1| rxx(state, theta, p0, p1), |channels| &channels.rxx);
+    }
+
+    fn ry(&mut self, theta: f64, q: usize) {
+        self.gate(&[], q, ry(theta), |channels| &channels.ry);
+    }
+
+    fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
+        // S X S^dagger = Y
+        let unitary = |state: &mut State, p0: usize, p1: usize| {
+            state.apply(&phase(-FRAC_PI_2), &[], p0);
+            state.apply(&phase(-FRAC_PI_2), &[], p1);
+            rxx(state, theta, p0, p1);
+            state.apply(&phase(FRAC_PI_2), &[], p0);
+            state.apply(&phase(FRAC_PI_2), &[], p1);
+        };
+        self.two_qubit_gate(q0, q1, unitary, |channels| &channels.ryy);
+    }
+
+    fn rz(&mut self, theta: f64, q: usize) {
+        self.gate(&[], q, rz(theta), |channels| &channels.rz);
+    }
+
+    fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.two_qubit_gate(q0, q1, |state, p0, p1| rzz(state, theta, p0, p1), |channels| &channels.rzz);
+    }
+
+    fn sadj(&mut self, q: usize) {
+        self.gate(&[], q, phase(-FRAC_PI_2), |channels| &channels.sadj);
+    }
+
+    fn s(&mut self, q: usize) {
+        self.gate(&[], q, phase(FRAC_PI_2), |channels| &channels.s);
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        let unitary = |state: &mut State, p0: usize, p1: usize| {
+            state.apply(&pauli('X'), &[p0], p1);
+            state.apply(&pauli('X'), &[p1], p0);
+            state.apply(&pauli('X'), &[p0], p1);
+        };
+        self.two_qubit_gate(q0, q1, unitary, |channels| &channels.swap);
+    }
+
+    fn tadj(&mut self, q: usize) {
+        self.gate(&[], q, phase(-FRAC_PI_4), |channels| &channels.tadj);
+    }
+
+    fn t(&mut self, q: usize) {
+        self.gate(&[], q, phase(FRAC_PI_4), |channels| &channels.t);
+    }
+
+    fn x(&mut self, q: usize) {
+        self.gate(&[], q, pauli('X'), |channels| &channels.x);
+    }
+
+    fn y(&mut self, q: usize) {
+        self.gate(&[], q, pauli('Y'), |channels| &channels.y);
+    }
+
+    fn z(&mut self, q: usize) {
+        self.gate(&[], q, pauli('Z'), |channels| &channels.z);
+    }
+
+    fn qubit_allocate(&mut self) -> usize {
+        let id = (0..).find(|id| !self.qubits.contains(id)).unwrap_or_default();
+        if self.qubits.len() == MAX_QUBITS {
+            // gates on qubits that are not part of the state are ignored, the shot fails afterwards
+            self.error = Some(format!("The density matrix simulator supports at most {MAX_QUBITS} qubits"));
+            return id;
+        }
+        self.state.expand();
+        self.qubits.push(id);
+        id
+    }
+
+    fn qubit_release(&mut self, q: usize) -> bool {
+        let Some(target) = self.position(q) else {
+            return true;
+        };
+        let is_zero = self.state.probability_of_one(target) < 1e-10;
+        self.state.trace_out(target);
+        self.qubits.remove(target);
+        is_zero
+    }
+
+    fn qubit_swap_id(&mut self, q0: usize, q1: usize) {
+        for id in self.qubits.iter_mut() {
+            if *id == q0 {
+                *id = q1;
+            } else if *id == q1 {
+                *id = q0;
+            }
+        }
+    }
+
+    /// The state vector when the state is pure, otherwise no amplitudes.
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex64)>, usize) {
+        let dim = self.state.dim;
+        let rho = &self.state.rho;
+        let purity: f64 = rho.iter().map(|v| v.norm_sqr()).sum();
+        if purity < 1.0 - 1e-9 {
+            return (Vec::new(), self.qubits.len());
+        }
+
+        // every column of a pure state is proportional to the state vector
+        let column = (0..dim)
+            .max_by(|&a, &b| rho[a * dim + a].re.total_cmp(&rho[b * dim + b].re))
+            .unwrap_or_default();
+        let norm = rho[column * dim + column].re.sqrt();
+        let amplitudes = (0..dim)
+            .map(|row| (row, rho[row * dim + column] / norm))
+            .filter(|(_, amplitude)| amplitude.norm() > 1e-12)
+            .map(|(row, amplitude)| {
+                let mut id = BigUint::from(0u32);
+                for (position, qubit) in self.qubits.iter().enumerate() {
+                    if row & (1 << position) != 0 {
+                        id.set_bit(*qubit as u64, true);
+                    }
+                }
+                (id, amplitude)
+            })
+            .collect();
+        (amplitudes, self.qubits.len())
+    }
+
+    fn qubit_is_zero(&mut self, q: usize) -> bool {
+        self.position(q)
+            .is_none_or(|target| self.state.probability_of_one(target) < 1e-10)
+    }
+
+    fn set_seed(&mut self, seed: Option<u64>) {
+        self.rng = seed.map_or_else(StdRng::from_entropy, StdRng::seed_from_u64);
+    }
+}
@@ -0,0 +1,649 @@
+use serde_json::{Map, Value, json};
+
+use crate::sim::{QsError, estimate, estimate_expression};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PredefinedQubitModel {
+    GateNsE3,
+    GateNsE4,
+    GateUsE3,
+    GateUsE4,
+    MajNsE4,
+    MajNsE6,
+}
+
+impl PredefinedQubitModel {
+    pub const ALL: [PredefinedQubitModel; 6] = [
+        PredefinedQubitModel::GateNsE3,
+        PredefinedQubitModel::GateNsE4,
+        PredefinedQubitModel::GateUsE3,
+        PredefinedQubitModel::GateUsE4,
+        PredefinedQubitModel::MajNsE4,
+        PredefinedQubitModel::MajNsE6,
+    ];
+
+    /// The name the resource estimator uses for this model, e.g. `qubit_gate_ns_e3`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PredefinedQubitModel::GateNsE3 => "qubit_gate_ns_e3",
+            PredefinedQubitModel::GateNsE4 => "qubit_gate_ns_e4",
+            PredefinedQubitModel::GateUsE3 => "qubit_gate_us_e3",
+            PredefinedQubitModel::GateUsE4 => "qubit_gate_us_e4",
+            PredefinedQubitModel::MajNsE4 => "qubit_maj_ns_e4",
+            PredefinedQubitModel::MajNsE6 => "qubit_maj_ns_e6",
+        }
+    }
+
+    pub fn instruction_set(&self) -> InstructionSet {
+        match self {
+            PredefinedQubitModel::MajNsE4 | PredefinedQubitModel::MajNsE6 => InstructionSet::Majorana,
+            _ => InstructionSet::GateBased,
+        }
+    }
+
+    /// The parameters of the model, as documented for the resource estimator.
+    pub fn params(&self) -> CustomQubitParams {
+        let gate_based = |measurement_time_ns: u64, time_ns: u64, error_rate: f64, t_gate_error_rate: f64| {
+            CustomQubitParams {
+                name: self.name().to_string(),
+                instruction_set: InstructionSet::GateBased,
+                one_qubit_measurement_time_ns: measurement_time_ns,
+                one_qubit_gate_time_ns: Some(time_ns),
+                two_qubit_gate_time_ns: Some(time_ns),
+                two_qubit_joint_measurement_time_ns: None,
+                t_gate_time_ns: Some(time_ns),
+                one_qubit_measurement_error_rate: error_rate,
+                one_qubit_gate_error_rate: Some(error_rate),
+                two_qubit_gate_error_rate: Some(error_rate),
+                two_qubit_joint_measurement_error_rate: None,
+                t_gate_error_rate,
+                idle_error_rate: Some(error_rate),
+            }
+        };
+        let majorana = |error_rate: f64, t_gate_error_rate: f64| CustomQubitParams {
+            name: self.name().to_string(),
+            instruction_set: InstructionSet::Majorana,
+            one_qubit_measurement_time_ns: 100,
+            one_qubit_gate_time_ns: None,
+            two_qubit_gate_time_ns: None,
+            two_qubit_joint_measurement_time_ns: Some(100),
+            t_gate_time_ns: Some(100),
+            one_qubit_measurement_error_rate: error_rate,
+            one_qubit_gate_error_rate: None,
+            two_qubit_gate_error_rate: None,
+            two_qubit_joint_measurement_error_rate: Some(error_rate),
+            t_gate_error_rate,
+            idle_error_rate: Some(error_rate),
+        };
+
+        match self {
+            PredefinedQubitModel::GateNsE3 => gate_based(100, 50, 1e-3, 1e-3),
+            PredefinedQubitModel::GateNsE4 => gate_based(100, 50, 1e-4, 1e-4),
+            PredefinedQubitModel::GateUsE3 => gate_based(100_000, 100_000, 1e-3, 1e-6),
+            PredefinedQubitModel::GateUsE4 => gate_based(100_000, 100_000, 1e-4, 1e-6),
+            PredefinedQubitModel::MajNsE4 => majorana(1e-4, 0.05),
+            PredefinedQubitModel::MajNsE6 => majorana(1e-6, 0.01),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InstructionSet {
+    GateBased,
+    Majorana,
+}
+
+/// Physical qubit parameters. Times are in nanoseconds.
+/// Gate-based qubits need the one- and two-qubit gate parameters,
+/// Majorana qubits need the two-qubit joint measurement parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CustomQubitParams {
+    pub name: String,
+    pub instruction_set: InstructionSet,
+    pub one_qubit_measurement_time_ns: u64,
+    pub one_qubit_gate_time_ns: Option<u64>,
+    pub two_qubit_gate_time_ns: Option<u64>,
+    pub two_qubit_joint_measurement_time_ns: Option<u64>,
+    pub t_gate_time_ns: Option<u64>,
+    pub one_qubit_measurement_error_rate: f64,
+    pub one_qubit_gate_error_rate: Option<f64>,
+    pub two_qubit_gate_error_rate: Option<f64>,
+    pub two_qubit_joint_measurement_error_rate: Option<f64>,
+    pub t_gate_error_rate: f64,
+    pub idle_error_rate: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum QubitModel {
+    Predefined { model: PredefinedQubitModel },
+    Custom { params: CustomQubitParams },
+}
+
+impl QubitModel {
+    fn instruction_set(&self) -> InstructionSet {
+        match self {
+            QubitModel::Predefined { model } => model.instruction_set(),
+            QubitModel::Custom { params } => params.instruction_set,
+        }
+    }
+
+    pub fn params(&self) -> CustomQubitParams {
+        match self {
+            QubitModel::Predefined { model } => model.params(),
+            QubitModel::Custom { params } => params.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QecSchemeName {
+    SurfaceCode,
+    FloquetCode,
+}
+
+/// A predefined QEC scheme, optionally with some of its parameters overridden.
+/// The formulas use the syntax of the resource estimator.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QecScheme {
+    pub name: QecSchemeName,
+    pub error_correction_threshold: Option<f64>,
+    pub crossing_prefactor: Option<f64>,
+    pub logical_cycle_time: Option<String>,
+    pub physical_qubits_per_logical_qubit: Option<String>,
+    pub max_code_distance: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ErrorBudget {
+    Total { budget: f64 },
+    Split { logical: f64, t_states: f64, rotations: f64 },
+}
+
+/// Times are in nanoseconds. `max_duration_ns` and `max_physical_qubits` are mutually exclusive.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EstimationConstraints {
+    pub logical_depth_factor: Option<f64>,
+    pub max_t_factories: Option<u32>,
+    pub max_duration_ns: Option<u64>,
+    pub max_physical_qubits: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DistillationUnitQubitSpecification {
+    pub num_unit_qubits: u32,
+    pub duration_in_qubit_cycle_time: u32,
+}
+
+/// Either a predefined unit selected by `name` (`15-1 RM` or `15-1 space-efficient`),
+/// or a custom unit with the T counts, the formulas and at least one qubit specification.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DistillationUnitSpecification {
+    pub name: Option<String>,
+    pub display_name: Option<String>,
+    pub num_input_ts: Option<u32>,
+    pub num_output_ts: Option<u32>,
+    pub failure_probability_formula: Option<String>,
+    pub output_error_rate_formula: Option<String>,
+    pub physical_qubit_specification: Option<DistillationUnitQubitSpecification>,
+    pub logical_qubit_specification: Option<DistillationUnitQubitSpecification>,
+    pub logical_qubit_specification_first_round_override: Option<DistillationUnitQubitSpecification>,
+}
+
+const PREDEFINED_DISTILLATION_UNITS: [&str; 2] = ["15-1 RM", "15-1 space-efficient"];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EstimateType {
+    /// A single estimate, with the shortest runtime.
+    #[default]
+    SinglePoint,
+    /// The estimates that trade runtime for physical qubits, see `EstimationResult::frontier`.
+    Frontier,
+}
+
+/// Job parameters of a single estimation. Unset fields use the estimator defaults.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EstimatorParams {
+    pub estimate_type: Option<EstimateType>,
+    pub qubit_model: Option<QubitModel>,
+    pub qec_scheme: Option<QecScheme>,
+    pub error_budget: Option<ErrorBudget>,
+    pub constraints: Option<EstimationConstraints>,
+    pub distillation_unit_specifications: Option<Vec<DistillationUnitSpecification>>,
+}
+
+pub fn estimate_with_params(source: &str, params: Vec<EstimatorParams>) -> Result<String, QsError> {
+    estimate(source, Some(estimator_params_json(params)?))
+}
+
+pub fn estimate_expression_with_params(expression: &str, params: Vec<EstimatorParams>) -> Result<String, QsError> {
+    estimate_expression(expression, Some(estimator_params_json(params)?))
+}
+
+/// Validates the configurations and serializes them into the job parameters JSON of the estimator.
+/// No configurations means a single default one.
+pub fn estimator_params_json(params: Vec<EstimatorParams>) -> Result<String, QsError> {
+    if params.is_empty() {
+        return Ok("[{}]".to_string());
+    }
+
+    let items = params
+        .iter()
+        .map(|p| p.to_json())
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Value::Array(items).to_string())
+}
+
+impl EstimatorParams {
+    pub fn validate(&self) -> Result<(), QsError> {
+        if let Some(qubit_model) = &self.qubit_model {
+            qubit_model.validate()?;
+        }
+        if let Some(qec_scheme) = &self.qec_scheme {
+            qec_scheme.validate()?;
+            let instruction_set = self
+                .qubit_model
+                .as_ref()
+                .map_or(InstructionSet::GateBased, QubitModel::instruction_set);
+            if qec_scheme.name == QecSchemeName::FloquetCode && instruction_set != InstructionSet::Majorana {
+                return Err(invalid("qec_scheme.name", "the floquet code requires a Majorana qubit model"));
+            }
+        }
+        if let Some(error_budget) = &self.error_budget {
+            error_budget.validate()?;
+        }
+        if let Some(constraints) = &self.constraints {
+            constraints.validate()?;
+        }
+        if let Some(units) = &self.distillation_unit_specifications {
+            for unit in units {
+                unit.validate()?;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn to_json(&self) -> Result<Value, QsError> {
+        self.validate()?;
+
+        let mut params = Map::new();
+        if let Some(estimate_type) = self.estimate_type {
+            params.insert(
+                "estimateType".into(),
+                json!(match estimate_type {
+                    EstimateType::SinglePoint => "singlePoint",
+                    EstimateType::Frontier => "frontier",
+                }),
+            );
+        }
+        if let Some(qubit_model) = &self.qubit_model {
+            params.insert("qubitParams".into(), qubit_model.to_json());
+        }
+        if let Some(qec_scheme) = &self.qec_scheme {
+            params.insert("qecScheme".into(), qec_scheme.to_json());
+        }
+        if let Some(error_budget) = &self.error_budget {
+            params.insert("errorBudget".into(), error_budget.to_json());
+        }
+        if let Some(constraints) = &self.constraints {
+            params.insert("constraints".into(), constraints.to_json());
+        }
+        if let Some(units) = &self.distillation_unit_specifications {
+            params.insert(
+                "distillationUnitSpecifications".into(),
+                Value::Array(units.iter().map(DistillationUnitSpecification::to_json).collect()),
+            );
+        }
+        Ok(Value::Object(params))
+    }
+}
+
+impl QubitModel {
+    pub(crate) fn validate(&self) -> Result<(), QsError> {
+        let QubitModel::Custom { params } = self else {
+            return Ok(());
+        };
+
+        if params.name.trim().is_empty() {
+            return Err(invalid("qubit_model.name", "must not be empty"));
+        }
+
+        let required: &[(&str, bool)] = match params.instruction_set {
+            InstructionSet::GateBased => &[
+                ("one_qubit_gate_time_ns", params.one_qubit_gate_time_ns.is_some()),
+                ("two_qubit_gate_time_ns", params.two_qubit_gate_time_ns.is_some()),
+                ("one_qubit_gate_error_rate", params.one_qubit_gate_error_rate.is_some()),
+                ("two_qubit_gate_error_rate", params.two_qubit_gate_error_rate.is_some()),
+            ],
+            InstructionSet::Majorana => &[
+                ("two_qubit_joint_measurement_time_ns", params.two_qubit_joint_measurement_time_ns.is_some()),
+                ("two_qubit_joint_measurement_error_rate", params.two_qubit_joint_measurement_error_rate.is_some()),
+            ],
+        };
+        for (name, is_set) in required {
+            if !is_set {
+                return Err(invalid(
+                    &format!("qubit_model.{name}"),
+                    &format!("is required for the {:?} instruction set", params.instruction_set),
+                ));
+            }
+        }
+
+        let times = [
+            ("one_qubit_measurement_time_ns", Some(params.one_qubit_measurement_time_ns)),
+            ("one_qubit_gate_time_ns", params.one_qubit_gate_time_ns),
+            ("two_qubit_gate_time_ns", params.two_qubit_gate_time_ns),
+            ("two_qubit_joint_measurement_time_ns", params.two_qubit_joint_measurement_time_ns),
+            ("t_gate_time_ns", params.t_gate_time_ns),
+        ];
+        for (name, time) in times {
+            if time == Some(0) {
+                return Err(invalid(&format!("qubit_model.{name}"), "must be positive"));
+            }
+        }
+
+        let rates = [
+            ("one_qubit_measurement_error_rate", Some(params.one_qubit_measurement_error_rate)),
+            ("one_qubit_gate_error_rate", params.one_qubit_gate_error_rate),
+            ("two_qubit_gate_error_rate", params.two_qubit_gate_error_rate),
+            ("two_qubit_joint_measurement_error_rate", params.two_qubit_joint_measurement_error_rate),
+            ("t_gate_error_rate", Some(params.t_gate_error_rate)),
+            ("idle_error_rate", params.idle_error_rate),
+        ];
+        for (name, rate) in rates {
+            if let Some(rate) = rate {
+                check_probability(&format!("qubit_model.{name}"), rate)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn to_json(&self) -> Value {
+        let params = match self {
+            QubitModel::Predefined { model } => return json!({ "name": model.name() }),
+            QubitModel::Custom { params } => params,
+        };
+
+        let mut json = Map::new();
+        json.insert("name".into(), json!(params.name));
+        json.insert(
+            "instructionSet".into(),
+            json!(match params.instruction_set {
+                InstructionSet::GateBased => "GateBased",
+                InstructionSet::Majorana => "Majorana",
+            }),
+        );
+        let times = [
+            ("oneQubitMeasurementTime", Some(params.one_qubit_measurement_time_ns)),
+            ("oneQubitGateTime", params.one_qubit_gate_time_ns),
+            ("twoQubitGateTime", params.two_qubit_gate_time_ns),
+            ("twoQubitJointMeasurementTime", params.two_qubit_joint_measurement_time_ns),
+            ("tGateTime", params.t_gate_time_ns),
+        ];
+        for (key, time) in times {
+            if let Some(time) = time {
+                json.insert(key.into(), json!(format_ns(time)));
+            }
+        }
+        let rates = [
+            ("oneQubitMeasurementErrorRate", Some(params.one_qubit_measurement_error_rate)),
+            ("oneQubitGateErrorRate", params.one_qubit_gate_error_rate),
+            ("twoQubitGateErrorRate", params.two_qubit_gate_error_rate),
+            ("twoQubitJointMeasurementErrorRate", params.two_qubit_joint_measurement_error_rate),
+            ("tGateErrorRate", Some(params.t_gate_error_rate)),
+            ("idleErrorRate", params.idle_error_rate),
+        ];
+        for (key, rate) in rates {
+            if let Some(rate) = rate {
+                json.insert(key.into(), json!(rate));
+            }
+        }
+        Value::Object(json)
+    }
+}
+
+impl QecScheme {
+    fn validate(&self) -> Result<(), QsError> {
+        if let Some(threshold) = self.error_correction_threshold {
+            if threshold <= 0.0 || threshold >= 1.0 {
+                return Err(invalid("qec_scheme.error_correction_threshold", "must be between 0 and 1"));
+            }
+        }
+        if let Some(prefactor) = self.crossing_prefactor {
+            if prefactor <= 0.0 {
+                return Err(invalid("qec_scheme.crossing_prefactor", "must be positive"));
+            }
+        }
+        if self.max_code_distance == Some(0) {
+            return Err(invalid("qec_scheme.max_code_distance", "must be positive"));
+        }
+        Ok(())
+    }
+
+    fn to_json(&self) -> Value {
+        let mut json = Map::new();
+        json.insert(
+            "name".into(),
+            json!(match self.name {
+                QecSchemeName::SurfaceCode => "surface_code",
+                QecSchemeName::FloquetCode => "floquet_code",
+            }),
+        );
+        if let Some(threshold) = self.error_correction_threshold {
+            json.insert("errorCorrectionThreshold".into(), json!(threshold));
+        }
+        if let Some(prefactor) = self.crossing_prefactor {
+            json.insert("crossingPrefactor".into(), json!(prefactor));
+        }
+        if let Some(formula) = &self.logical_cycle_time {
+            json.insert("logicalCycleTime".into(), json!(formula));
+        }
+        if let Some(formula) = &self.physical_qubits_per_logical_qubit {
+            json.insert("physicalQubitsPerLogicalQubit".into(), json!(formula));
+        }
+        if let Some(distance) = self.max_code_distance {
+            json.insert("maxCodeDistance".into(), json!(distance));
+        }
+        Value::Object(json)
+    }
+}
+
+impl ErrorBudget {
+    fn validate(&self) -> Result<(), QsError> {
+        match self {
+            ErrorBudget::Total { budget } => {
+                if *budget <= 0.0 || *budget >= 1.0 {
+                    return Err(invalid("error_budget.budget", "must be between 0 and 1"));
+                }
+            }
+            ErrorBudget::Split {
+                logical,
+                t_states,
+                rotations,
+            } => {
+                for (name, part) in [("logical", logical), ("t_states", t_states), ("rotations", rotations)] {
+                    if *part < 0.0 {
+                        return Err(invalid(&format!("error_budget.{name}"), "must not be negative"));
+                    }
+                }
+                let total = logical + t_states + rotations;
+                if total <= 0.0 || total >= 1.0 {
+                    return Err(invalid("error_budget", "the parts must sum to a value between 0 and 1"));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn to_json(&self) -> Value {
+        match self {
+            ErrorBudget::Total { budget } => json!(budget),
+            ErrorBudget::Split {
+                logical,
+                t_states,
+                rotations,
+            } => json!({ "logical": logical, "tstates": t_states, "rotations": rotations }),
+        }
+    }
+}
+
+impl EstimationConstraints {
+    fn validate(&self) -> Result<(), QsError> {
+        if let Some(factor) = self.logical_depth_factor {
+            if factor < 1.0 {
+                return Err(invalid("constraints.logical_depth_factor", "must be at least 1"));
+            }
+        }
+        if self.max_t_factories == Some(0) {
+            return Err(invalid("constraints.max_t_factories", "must be positive"));
+        }
+        if self.max_duration_ns == Some(0) {
+            return Err(invalid("constraints.max_duration_ns", "must be positive"));
+        }
+        if self.max_physical_qubits == Some(0) {
+            return Err(invalid("constraints.max_physical_qubits", "must be positive"));
+        }
+        if self.max_duration_ns.is_some() && self.max_physical_qubits.is_some() {
+            return Err(invalid(
+                "constraints",
+                "max_duration_ns and max_physical_qubits cannot be set at the same time",
+            ));
+        }
+        Ok(())
+    }
+
+    fn to_json(&self) -> Value {
+        let mut json = Map::new();
+        if let Some(factor) = self.logical_depth_factor {
+            json.insert("logicalDepthFactor".into(), json!(factor));
+        }
+        if let Some(max) = self.max_t_factories {
+            json.insert("maxTFactories".into(), json!(max));
+        }
+        if let Some(duration) = self.max_duration_ns {
+            json.insert("maxDuration".into(), json!(format_ns(duration)));
+        }
+        if let Some(max) = self.max_physical_qubits {
+            json.insert("maxPhysicalQubits".into(), json!(max));
+        }
+        Value::Object(json)
+    }
+}
+
+impl DistillationUnitSpecification {
+    fn validate(&self) -> Result<(), QsError> {
+        let is_custom = self.num_input_ts.is_some()
+            || self.num_output_ts.is_some()
+            || self.failure_probability_formula.is_some()
+            || self.output_error_rate_formula.is_some()
+            || self.physical_qubit_specification.is_some()
+            || self.logical_qubit_specification.is_some()
+            || self.logical_qubit_specification_first_round_override.is_some();
+
+        if let Some(name) = &self.name {
+            if !PREDEFINED_DISTILLATION_UNITS.contains(&name.as_str()) {
+                return Err(invalid(
+                    "distillation_unit_specifications.name",
+                    &format!("unknown unit `{name}`, expected one of {PREDEFINED_DISTILLATION_UNITS:?}"),
+                ));
+            }
+            if is_custom {
+                return Err(invalid(
+                    "distillation_unit_specifications.name",
+                    "predefined units cannot be combined with custom parameters",
+                ));
+            }
+            return Ok(());
+        }
+
+        let required = [
+            ("num_input_ts", self.num_input_ts.is_some()),
+            ("num_output_ts", self.num_output_ts.is_some()),
+            ("failure_probability_formula", self.failure_probability_formula.is_some()),
+            ("output_error_rate_formula", self.output_error_rate_formula.is_some()),
+        ];
+        for (name, is_set) in required {
+            if !is_set {
+                return Err(invalid(
+                    &format!("distillation_unit_specifications.{name}"),
+                    "is required for custom units",
+                ));
+            }
+        }
+        if self.physical_qubit_specification.is_none() && self.logical_qubit_specification.is_none() {
+            return Err(invalid(
+                "distillation_unit_specifications",
+                "custom units need a physical or a logical qubit specification",
+            ));
+        }
+        if self.logical_qubit_specification_first_round_override.is_some() && self.logical_qubit_specification.is_none() {
+            return Err(invalid(
+                "distillation_unit_specifications.logical_qubit_specification_first_round_override",
+                "requires a logical qubit specification",
+            ));
+        }
+        if self.num_input_ts == Some(0) || self.num_output_ts == Some(0) {
+            return Err(invalid("distillation_unit_specifications", "T counts must be positive"));
+        }
+        Ok(())
+    }
+
+    fn to_json(&self) -> Value {
+        let mut json = Map::new();
+        if let Some(name) = &self.name {
+            json.insert("name".into(), json!(name));
+        }
+        if let Some(display_name) = &self.display_name {
+            json.insert("displayName".into(), json!(display_name));
+        }
+        if let Some(count) = self.num_input_ts {
+            json.insert("numInputTs".into(), json!(count));
+        }
+        if let Some(count) = self.num_output_ts {
+            json.insert("numOutputTs".into(), json!(count));
+        }
+        if let Some(formula) = &self.failure_probability_formula {
+            json.insert("failureProbabilityFormula".into(), json!(formula));
+        }
+        if let Some(formula) = &self.output_error_rate_formula {
+            json.insert("outputErrorRateFormula".into(), json!(formula));
+        }
+        let specifications = [
+            ("physicalQubitSpecification", &self.physical_qubit_specification),
+            ("logicalQubitSpecification", &self.logical_qubit_specification),
+            (
+                "logicalQubitSpecificationFirstRoundOverride",
+                &self.logical_qubit_specification_first_round_override,
+            ),
+        ];
+        for (key, specification) in specifications {
+            if let Some(specification) = specification {
+                json.insert(
+                    key.into(),
+                    json!({
+                        "numUnitQubits": specification.num_unit_qubits,
+                        "durationInQubitCycleTime": specification.duration_in_qubit_cycle_time,
+                    }),
+                );
+            }
+        }
+        Value::Object(json)
+    }
+}
+
+fn format_ns(time: u64) -> String {
+    format!("{time} ns")
+}
+
+fn check_probability(parameter: &str, value: f64) -> Result<(), QsError> {
+    if !(0.0..=1.0).contains(&value) {
+        return Err(invalid(parameter, "must be between 0 and 1"));
+    }
+    Ok(())
+}
+
+fn invalid(parameter: &str, reason: &str) -> QsError {
+    QsError::InvalidParameter {
+        parameter: parameter.to_string(),
+        reason: reason.to_string(),
+    }
+}
@@ -0,0 +1,98 @@
+use crate::estimation::{EstimateType, EstimatorParams, PredefinedQubitModel, QubitModel};
+use crate::estimation_result::{EstimationResult, FrontierEntry, estimate_results};
+use crate::sim::QsError;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EstimationComparisonRow {
+    /// Qubit model and QEC scheme of the configuration, e.g. `qubit_gate_ns_e3 (surface_code)`.
+    pub name: String,
+    pub runtime_ns: u64,
+    pub physical_qubits: u64,
+    pub rqops: u64,
+    /// Share of the physical qubits spent on T factories.
+    pub t_factory_fraction: f64,
+    /// Whether no other configuration is both faster and smaller.
+    pub is_pareto_optimal: bool,
+}
+
+/// One configuration for each predefined qubit model, with the default QEC scheme of the model.
+pub fn predefined_estimator_params() -> Vec<EstimatorParams> {
+    PredefinedQubitModel::ALL
+        .iter()
+        .map(|&model| EstimatorParams {
+            qubit_model: Some(QubitModel::Predefined { model }),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Estimates the program under each configuration, or under all predefined qubit models when none are given.
+pub fn estimate_batch(source: &str, params: Option<Vec<EstimatorParams>>) -> Result<Vec<EstimationResult>, QsError> {
+    let params = params
+        .filter(|params| !params.is_empty())
+        .unwrap_or_else(predefined_estimator_params);
+    estimate_results(source, params)
+}
+
+/// Runs a frontier estimation of the program and returns its points, from the shortest runtime to the fewest qubits.
+pub fn estimate_frontier(source: &str, params: Option<EstimatorParams>) -> Result<Vec<FrontierEntry>, QsError> {
+    let params = EstimatorParams {
+        estimate_type: Some(EstimateType::Frontier),
+        ..params.unwrap_or_default()
+    };
+    let mut results = estimate_results(source, vec![params])?;
+    Ok(results.pop().map(|result| result.frontier).unwrap_or_default())
+}
+
+/// Keeps the results that are not dominated in both runtime and physical qubits, ordered by runtime.
+pub fn pareto_frontier(results: Vec<EstimationResult>) -> Vec<EstimationResult> {
+    let optimal = pareto_optimal(&results);
+    let mut frontier: Vec<EstimationResult> = results
+        .into_iter()
+        .zip(optimal)
+        .filter_map(|(result, is_optimal)| is_optimal.then_some(result))
+        .collect();
+    frontier.sort_by_key(|r| (r.physical_counts.runtime_ns, r.physical_counts.physical_qubits));
+    frontier
+}
+
+pub fn comparison_table(results: Vec<EstimationResult>) -> Vec<EstimationComparisonRow> {
+    let optimal = pareto_optimal(&results);
+    results
+        .iter()
+        .zip(optimal)
+        .map(|(result, is_pareto_optimal)| {
+            let counts = &result.physical_counts;
+            EstimationComparisonRow {
+                name: configuration_name(result),
+                runtime_ns: counts.runtime_ns,
+                physical_qubits: counts.physical_qubits,
+                rqops: counts.rqops,
+                t_factory_fraction: if counts.physical_qubits == 0 {
+                    0.0
+                } else {
+                    counts.breakdown.physical_qubits_for_t_factories as f64 / counts.physical_qubits as f64
+                },
+                is_pareto_optimal,
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn configuration_name(result: &EstimationResult) -> String {
+    format!("{} ({})", result.qubit_model, result.qec_scheme)
+}
+
+fn pareto_optimal(results: &[EstimationResult]) -> Vec<bool> {
+    let point = |r: &EstimationResult| (r.physical_counts.runtime_ns, r.physical_counts.physical_qubits);
+    results
+        .iter()
+        .map(|candidate| {
+            let (runtime, qubits) = point(candidate);
+            !results.iter().any(|other| {
+                let (other_runtime, other_qubits) = point(other);
+                other_runtime <= runtime && other_qubits <= qubits && (other_runtime < runtime || other_qubits < qubits)
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,331 @@
+use serde_json::Value;
+
+use crate::estimation::{EstimatorParams, estimator_params_json};
+use crate::sim::{QsError, estimate, estimate_expression};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EstimationResult {
+    /// Name of the qubit model the estimate was computed for, e.g. `qubit_gate_ns_e3`.
+    pub qubit_model: String,
+    /// Name of the QEC scheme the estimate was computed for, e.g. `surface_code`.
+    pub qec_scheme: String,
+    pub physical_counts: PhysicalCounts,
+    pub logical_counts: LogicalCounts,
+    pub logical_qubit: LogicalQubit,
+    /// Missing when the program needs no T states.
+    pub t_factory: Option<TFactory>,
+    pub error_budget: EstimatedErrorBudget,
+    pub report_groups: Vec<ReportGroup>,
+    /// Assumptions the estimate is based on, as listed in the report.
+    pub assumptions: Vec<String>,
+    /// The points of a frontier estimate, in the order of the estimator, which starts with the shortest runtime.
+    /// The other fields then describe the first point. Empty for single-point estimates.
+    pub frontier: Vec<FrontierEntry>,
+}
+
+/// One estimate of a frontier estimation, trading runtime for physical qubits.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrontierEntry {
+    pub physical_counts: PhysicalCounts,
+    pub logical_qubit: LogicalQubit,
+    /// Missing when the program needs no T states.
+    pub t_factory: Option<TFactory>,
+    pub error_budget: EstimatedErrorBudget,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PhysicalCounts {
+    pub physical_qubits: u64,
+    pub runtime_ns: u64,
+    /// Reliable quantum operations per second.
+    pub rqops: u64,
+    pub breakdown: PhysicalCountsBreakdown,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PhysicalCountsBreakdown {
+    pub algorithmic_logical_qubits: u64,
+    pub algorithmic_logical_depth: u64,
+    pub logical_depth: u64,
+    pub num_t_states: u64,
+    pub clock_frequency: f64,
+    pub num_t_factories: u64,
+    pub num_t_factory_runs: u64,
+    pub physical_qubits_for_t_factories: u64,
+    pub physical_qubits_for_algorithm: u64,
+    pub required_logical_qubit_error_rate: f64,
+    pub required_logical_t_state_error_rate: Option<f64>,
+    pub num_ts_per_rotation: Option<u64>,
+    pub clifford_error_rate: f64,
+}
+
+/// Logical resources of a program, as counted before the physical estimation.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LogicalCounts {
+    pub num_qubits: u64,
+    pub t_count: u64,
+    pub rotation_count: u64,
+    pub rotation_depth: u64,
+    pub ccz_count: u64,
+    pub ccix_count: u64,
+    pub measurement_count: u64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogicalQubit {
+    pub code_distance: u64,
+    pub physical_qubits: u64,
+    pub logical_cycle_time_ns: u64,
+    pub logical_error_rate: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TFactory {
+    pub physical_qubits: u64,
+    pub runtime_ns: u64,
+    pub num_t_states: u64,
+    pub num_input_t_states: u64,
+    pub num_rounds: u64,
+    pub num_units_per_round: Vec<u64>,
+    pub unit_name_per_round: Vec<String>,
+    pub code_distance_per_round: Vec<u64>,
+    pub physical_qubits_per_round: Vec<u64>,
+    pub runtime_per_round_ns: Vec<u64>,
+    pub logical_error_rate: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EstimatedErrorBudget {
+    pub logical: f64,
+    pub t_states: f64,
+    pub rotations: f64,
+}
+
+/// A group of the formatted estimation report, with the values already resolved.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReportGroup {
+    pub title: String,
+    pub always_visible: bool,
+    pub entries: Vec<ReportEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReportEntry {
+    pub label: String,
+    pub value: String,
+    pub description: String,
+}
+
+pub fn estimate_results(source: &str, params: Vec<EstimatorParams>) -> Result<Vec<EstimationResult>, QsError> {
+    let json = estimate(source, Some(estimator_params_json(params)?))?;
+    parse_estimation_results(&json)
+}
+
+pub fn estimate_expression_results(expression: &str, params: Vec<EstimatorParams>) -> Result<Vec<EstimationResult>, QsError> {
+    let json = estimate_expression(expression, Some(estimator_params_json(params)?))?;
+    parse_estimation_results(&json)
+}
+
+/// Converts the JSON returned by `estimate` and `estimate_expression` into typed results.
+pub fn parse_estimation_results(json: &str) -> Result<Vec<EstimationResult>, QsError> {
+    let value: Value = serde_json::from_str(json).map_err(|error| QsError::ErrorMessage {
+        error_text: format!("Invalid estimation result: {error}"),
+    })?;
+
+    match &value {
+        Value::Array(items) => items.iter().map(parse_estimation_result).collect(),
+        item => Ok(vec![parse_estimation_result(item)?]),
+    }
+}
+
+fn parse_estimation_result(v: &Value) -> Result<EstimationResult, QsError> {
+    if let Some(status) = v["status"].as_str() {
+        if status != "success" {
+            return Err(QsError::ErrorMessage {
+                error_text: format!("Estimation failed: {}", v["error"]),
+            });
+        }
+    }
+
+    // a frontier estimate only has counts in its entries, the first one stands in for the result
+    let entries = v["frontierEntries"].as_array().map(Vec::as_slice).unwrap_or_default();
+    let frontier = entries.iter().map(parse_frontier_entry).collect::<Result<Vec<_>, _>>()?;
+    let first_entry;
+    let v = match (v, entries.first()) {
+        (Value::Object(result), Some(Value::Object(entry))) => {
+            let mut result = result.clone();
+            result.extend(entry.clone());
+            first_entry = Value::Object(result);
+            &first_entry
+        }
+        _ => v,
+    };
+
+    let qubit_model = string(v, "jobParams/qubitParams/name")?;
+    let qec_scheme = string(v, "jobParams/qecScheme/name")?;
+    let point = parse_frontier_entry(v)?;
+    Ok(EstimationResult {
+        qubit_model,
+        qec_scheme,
+        physical_counts: point.physical_counts,
+        logical_counts: parse_logical_counts(&v["logicalCounts"])?,
+        logical_qubit: point.logical_qubit,
+        t_factory: point.t_factory,
+        error_budget: point.error_budget,
+        report_groups: parse_report_groups(v)?,
+        assumptions: v["reportData"]["assumptions"]
+            .as_array()
+            .map(|items| items.iter().filter_map(|a| a.as_str().map(str::to_string)).collect())
+            .unwrap_or_default(),
+        frontier,
+    })
+}
+
+// The counts of a single estimate, as found in a result or in each entry of a frontier.
+fn parse_frontier_entry(v: &Value) -> Result<FrontierEntry, QsError> {
+    let breakdown = &v["physicalCounts"]["breakdown"];
+    let t_factory = &v["tfactory"];
+
+    Ok(FrontierEntry {
+        physical_counts: PhysicalCounts {
+            physical_qubits: uint(v, "physicalCounts/physicalQubits")?,
+            runtime_ns: uint(v, "physicalCounts/runtime")?,
+            rqops: uint(v, "physicalCounts/rqops")?,
+            breakdown: PhysicalCountsBreakdown {
+                algorithmic_logical_qubits: uint(breakdown, "algorithmicLogicalQubits")?,
+                algorithmic_logical_depth: uint(breakdown, "algorithmicLogicalDepth")?,
+                logical_depth: uint(breakdown, "logicalDepth")?,
+                num_t_states: uint(breakdown, "numTstates")?,
+                clock_frequency: float(breakdown, "clockFrequency")?,
+                num_t_factories: uint(breakdown, "numTfactories")?,
+                num_t_factory_runs: uint(breakdown, "numTfactoryRuns")?,
+                physical_qubits_for_t_factories: uint(breakdown, "physicalQubitsForTfactories")?,
+                physical_qubits_for_algorithm: uint(breakdown, "physicalQubitsForAlgorithm")?,
+                required_logical_qubit_error_rate: float(breakdown, "requiredLogicalQubitErrorRate")?,
+                required_logical_t_state_error_rate: breakdown["requiredLogicalTstateErrorRate"].as_f64(),
+                num_ts_per_rotation: breakdown["numTsPerRotation"].as_u64(),
+                clifford_error_rate: float(breakdown, "cliffordErrorRate")?,
+            },
+        },
+        logical_qubit: LogicalQubit {
+            code_distance: uint(v, "logicalQubit/codeDistance")?,
+            physical_qubits: uint(v, "logicalQubit/physicalQubits")?,
+            logical_cycle_time_ns: uint(v, "logicalQubit/logicalCycleTime")?,
+            logical_error_rate: float(v, "logicalQubit/logicalErrorRate")?,
+        },
+        t_factory: if t_factory.is_object() {
+            Some(TFactory {
+                physical_qubits: uint(t_factory, "physicalQubits")?,
+                runtime_ns: uint(t_factory, "runtime")?,
+                num_t_states: uint(t_factory, "numTstates")?,
+                num_input_t_states: uint(t_factory, "numInputTstates")?,
+                num_rounds: uint(t_factory, "numRounds")?,
+                num_units_per_round: uints(t_factory, "numUnitsPerRound")?,
+                unit_name_per_round: strings(t_factory, "unitNamePerRound")?,
+                code_distance_per_round: uints(t_factory, "codeDistancePerRound")?,
+                physical_qubits_per_round: uints(t_factory, "physicalQubitsPerRound")?,
+                runtime_per_round_ns: uints(t_factory, "runtimePerRound")?,
+                logical_error_rate: float(t_factory, "logicalErrorRate")?,
+            })
+        } else {
+            None
+        },
+        error_budget: EstimatedErrorBudget {
+            logical: float(v, "errorBudget/logical")?,
+            t_states: float(v, "errorBudget/tstates")?,
+            rotations: float(v, "errorBudget/rotations")?,
+        },
+    })
+}
+
+pub(crate) fn parse_logical_counts(v: &Value) -> Result<LogicalCounts, QsError> {
+    Ok(LogicalCounts {
+        num_qubits: uint(v, "numQubits")?,
+        t_count: uint(v, "tCount")?,
+        rotation_count: uint(v, "rotationCount")?,
+        rotation_depth: uint(v, "rotationDepth")?,
+        ccz_count: uint(v, "cczCount")?,
+        ccix_count: uint(v, "ccixCount")?,
+        measurement_count: uint(v, "measurementCount")?,
+    })
+}
+
+// Report entries point into the result with `/`-separated paths, mostly into the formatted counts.
+fn parse_report_groups(v: &Value) -> Result<Vec<ReportGroup>, QsError> {
+    let Some(groups) = v["reportData"]["groups"].as_array() else {
+        return Ok(Vec::new());
+    };
+
+    groups
+        .iter()
+        .map(|group| {
+            let entries = group["entries"]
+                .as_array()
+                .map(Vec::as_slice)
+                .unwrap_or_default()
+                .iter()
+                .map(|entry| {
+                    let value = entry["path"].as_str().map_or(&Value::Null, |path| lookup(v, path));
+                    Ok(ReportEntry {
+                        label: string(entry, "label")?,
+                        value: match value {
+                            Value::String(s) => s.clone(),
+                            Value::Null => String::new(),
+                            other => other.to_string(),
+                        },
+                        description: string(entry, "description")?,
+                    })
+                })
+                .collect::<Result<Vec<_>, QsError>>()?;
+
+            Ok(ReportGroup {
+                title: string(group, "title")?,
+                always_visible: group["alwaysVisible"].as_bool().unwrap_or(false),
+                entries,
+            })
+        })
+        .collect()
+}
+
+fn lookup<'a>(v: &'a Value, path: &str) -> &'a Value {
+    path.split('/').fold(v, |v, key| &v[key])
+}
+
+fn missing(path: &str) -> QsError {
+    QsError::ErrorMessage {
+        error_text: format!("Invalid estimation result: missing or invalid `{path}`"),
+    }
+}
+
+fn uint(v: &Value, path: &str) -> Result<u64, QsError> {
+    lookup(v, path).as_u64().ok_or_else(|| missing(path))
+}
+
+fn float(v: &Value, path: &str) -> Result<f64, QsError> {
+    lookup(v, path).as_f64().ok_or_else(|| missing(path))
+}
+
+fn string(v: &Value, path: &str) -> Result<String, QsError> {
+    lookup(v, path)
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| missing(path))
+}
+
+fn uints(v: &Value, path: &str) -> Result<Vec<u64>, QsError> {
+    lookup(v, path)
+        .as_array()
+        .ok_or_else(|| missing(path))?
+        .iter()
+        .map(|item| item.as_u64().ok_or_else(|| missing(path)))
+        .collect()
+}
+
+fn strings(v: &Value, path: &str) -> Result<Vec<String>, QsError> {
+    lookup(v, path)
+        .as_array()
+        .ok_or_else(|| missing(path))?
+        .iter()
+        .map(|item| item.as_str().map(str::to_string).ok_or_else(|| missing(path)))
+        .collect()
+}
@@ -1,25 +1,128 @@
 uniffi::include_scaffolding!("qsharp-bridge");
 
+use crate::circuit::circuit_json;
+use crate::circuit::circuit_json_operation;
+use crate::circuit::CircuitGenerationOptions;
+use crate::circuit::CircuitGenerationMethod;
+use crate::circuit_model::circuit;
+use crate::circuit_model::circuit_operation;
+use crate::circuit_model::Circuit;
+use crate::circuit_model::CircuitColumn;
+use crate::circuit_model::CircuitOperation;
+use crate::circuit_model::CircuitQubit;
+use crate::circuit_model::CircuitRegister;
+use crate::counting::estimate_qasm;
+use crate::counting::estimate_qir;
+use crate::counting::qasm_logical_counts;
+use crate::counting::qir_logical_counts;
+use crate::estimation::CustomQubitParams;
+use crate::estimation::DistillationUnitQubitSpecification;
+use crate::estimation::DistillationUnitSpecification;
+use crate::estimation::ErrorBudget;
+use crate::estimation::EstimateType;
+use crate::estimation::EstimationConstraints;
+use crate::estimation::EstimatorParams;
+use crate::estimation::InstructionSet;
+use crate::estimation::PredefinedQubitModel;
+use crate::estimation::QecScheme;
+use crate::estimation::QecSchemeName;
+use crate::estimation::QubitModel;
+use crate::estimation::estimate_expression_with_params;
+use crate::estimation::estimate_with_params;
+use crate::estimation::estimator_params_json;
+use crate::estimation_comparison::EstimationComparisonRow;
+use crate::estimation_comparison::comparison_table;
+use crate::estimation_comparison::estimate_batch;
+use crate::estimation_comparison::estimate_frontier;
+use crate::estimation_comparison::pareto_frontier;
+use crate::estimation_comparison::predefined_estimator_params;
+use crate::estimation_result::EstimatedErrorBudget;
+use crate::estimation_result::EstimationResult;
+use crate::estimation_result::FrontierEntry;
+use crate::estimation_result::LogicalCounts;
+use crate::estimation_result::LogicalQubit;
+use crate::estimation_result::PhysicalCounts;
+use crate::estimation_result::PhysicalCountsBreakdown;
+use crate::estimation_result::ReportEntry;
+use crate::estimation_result::ReportGroup;
+use crate::estimation_result::TFactory;
+use crate::estimation_result::estimate_expression_results;
+use crate::estimation_result::estimate_results;
+use crate::estimation_result::parse_estimation_results;
+use crate::loss::LossStatistics;
+use crate::loss::loss_statistics;
+use crate::noise::DeviceNoiseModel;
+use crate::noise::IdleNoise;
+use crate::noise::KrausOperator;
 use crate::noise::Noise;
+use crate::noise::NoiseModel;
 use crate::noise::PauliNoiseDistribution;
+use crate::noise::TwoQubitNoise;
+use crate::noise_estimation::noise_from_qubit_model;
+use crate::noise_estimation::qubit_params_from_noise;
+use crate::noise_file::noise_from_json;
+use crate::noise_file::noise_model_from_json;
+use crate::noise_file::noise_model_from_toml;
+use crate::noise_file::noise_model_to_json;
+use crate::noise_file::noise_model_to_toml;
+use crate::noise_file::noise_to_json;
 use crate::qasm::QasmGenerationOptions;
 use crate::qasm::QasmResetBehavior;
 use crate::qasm::qasm2;
 use crate::qasm::qasm2_expression;
+use crate::readout::ReadoutError;
+use crate::readout::measurement_histogram;
+use crate::readout::mitigate_readout;
+use crate::readout::readout_calibration_matrix;
+use crate::report::estimate_report;
+use crate::report::ReportFormat;
 use crate::sim::ExecutionOptions;
 use crate::sim::ExecutionState;
+use crate::sim::MeasurementOutcome;
 use crate::sim::QsError;
 use crate::sim::QubitState;
+use crate::sim::Simulator;
 use crate::sim::estimate;
 use crate::sim::estimate_expression;
+use crate::sim::estimate_logical_counts;
 use crate::sim::qir;
 use crate::sim::run_qs;
 use crate::sim::run_qs_with_options;
+use crate::stats::circuit_stats;
+use crate::stats::circuit_stats_operation;
+use crate::stats::CircuitStats;
 use crate::quantikz::quantikz;
 use crate::quantikz::quantikz_operation;
 use crate::quantikz::QuantikzGenerationOptions;
+use crate::svg::circuit_svg;
+use crate::svg::circuit_svg_operation;
+use crate::svg::SvgGenerationOptions;
+use crate::sweep::NoiseSweepPoint;
+use crate::sweep::SuccessPredicate;
+use crate::sweep::noise_sweep;
+use crate::text::circuit_text;
+use crate::text::circuit_text_operation;
+use crate::text::TextGenerationOptions;
 
+pub mod circuit;
+pub mod circuit_model;
+pub mod counting;
+pub mod density_matrix;
+pub mod estimation;
+pub mod estimation_comparison;
+pub mod estimation_result;
+pub mod loss;
 pub mod noise;
+pub mod noise_estimation;
+pub mod noise_file;
+pub mod noisy_backend;
 pub mod qasm;
+pub mod readout;
 pub mod sim;
-pub mod quantikz;
\ No newline at end of file
+pub mod stabilizer;
+pub mod stats;
+pub mod quantikz;
+pub mod report;
+pub mod svg;
+pub mod sweep;
+pub mod text;
\ No newline at end of file
@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+
+use crate::sim::{ExecutionState, MeasurementOutcome};
+
+/// Qubit loss observed over the shots of a run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LossStatistics {
+    pub shots: u64,
+    /// Shots in which at least one qubit was lost.
+    pub shots_with_loss: u64,
+    /// Fraction of the shots in which at least one qubit was lost.
+    pub loss_rate: f64,
+    /// Number of shots in which each qubit was lost, keyed by allocation index.
+    pub qubit_loss_counts: HashMap<u64, u64>,
+    /// Number of shots by the number of qubits lost in them.
+    pub lost_qubits_histogram: HashMap<u64, u64>,
+    /// Fraction of the measurement outcomes that were `Loss`.
+    pub measurement_loss_rate: f64,
+}
+
+pub fn loss_statistics(results: Vec<ExecutionState>) -> LossStatistics {
+    let mut qubit_loss_counts = HashMap::new();
+    let mut lost_qubits_histogram = HashMap::new();
+    let mut shots_with_loss = 0;
+    let mut outcomes = 0;
+    let mut lost_outcomes = 0;
+
+    for result in &results {
+        if !result.lost_qubits.is_empty() {
+            shots_with_loss += 1;
+        }
+        for &qubit in &result.lost_qubits {
+            *qubit_loss_counts.entry(qubit).or_insert(0) += 1;
+        }
+        *lost_qubits_histogram.entry(result.lost_qubits.len() as u64).or_insert(0) += 1;
+
+        outcomes += result.outcomes.len();
+        lost_outcomes += result
+            .outcomes
+            .iter()
+            .filter(|&&outcome| outcome == MeasurementOutcome::Loss)
+            .count();
+    }
+
+    let rate = |count: usize, total: usize| if total == 0 { 0.0 } else { count as f64 / total as f64 };
+    LossStatistics {
+        shots: results.len() as u64,
+        shots_with_loss: shots_with_loss as u64,
+        loss_rate: rate(shots_with_loss, results.len()),
+        qubit_loss_counts,
+        lost_qubits_histogram,
+        measurement_loss_rate: rate(lost_outcomes, outcomes),
+    }
+}
@@ -1,5 +1,13 @@
+use std::collections::HashMap;
+
+use num_complex::Complex64;
+
+use crate::readout::ReadoutError;
 use crate::sim::QsError;
 
+/// A single-qubit operator, row-major.
+pub(crate) type Matrix2 = [[Complex64; 2]; 2];
+
 #[derive(Debug, Clone)]
 pub enum Noise {
     Ideal,
@@ -15,6 +23,24 @@ pub enum Noise {
     Depolarizing {
         p: f64,
     },
+    /// Relaxation towards the zero state. Needs the density matrix simulator, like all non-Pauli channels.
+    AmplitudeDamping {
+        gamma: f64,
+    },
+    /// Loss of phase coherence without loss of energy.
+    PhaseDamping {
+        lambda: f64,
+    },
+    /// Amplitude and phase damping during `gate_time`, in the same time unit as `t1` and `t2`.
+    ThermalRelaxation {
+        t1: f64,
+        t2: f64,
+        gate_time: f64,
+    },
+    /// A general single-qubit channel given by its Kraus operators.
+    Kraus {
+        operators: Vec<KrausOperator>,
+    },
 }
 
 impl Noise {
@@ -25,7 +51,133 @@ impl Noise {
             Noise::BitFlip { p } => PauliNoiseDistribution::new(*p, 0.0, 0.0),
             Noise::PhaseFlip { p } => PauliNoiseDistribution::new(0.0, 0.0, *p),
             Noise::Depolarizing { p } => PauliNoiseDistribution::new(*p / 3.0, *p / 3.0, *p / 3.0),
+            Noise::AmplitudeDamping { .. }
+            | Noise::PhaseDamping { .. }
+            | Noise::ThermalRelaxation { .. }
+            | Noise::Kraus { .. } => Err(QsError::ErrorMessage {
+                error_text: "Noise is not a Pauli channel, it needs the density matrix simulator".to_string(),
+            }),
+        }
+    }
+
+    /// The Kraus operators of the channel, an empty list for the ideal channel.
+    pub(crate) fn kraus_operators(&self) -> Result<Vec<Matrix2>, QsError> {
+        let invalid = |reason: &str| QsError::ErrorMessage {
+            error_text: format!("Invalid noise channel: {reason}"),
+        };
+        let probability = |p: f64, name: &str| {
+            if (0.0..=1.0).contains(&p) {
+                Ok(p)
+            } else {
+                Err(invalid(&format!("`{name}` must be between 0.0 and 1.0")))
+            }
+        };
+        let real = |m: [[f64; 2]; 2]| m.map(|row| row.map(|v| Complex64::new(v, 0.0)));
+
+        let operators = match self {
+            Noise::Ideal => Vec::new(),
+            Noise::Pauli { .. } | Noise::BitFlip { .. } | Noise::PhaseFlip { .. } | Noise::Depolarizing { .. } => {
+                let dist = self.to_distribution()?;
+                if dist.x + dist.y + dist.z == 0.0 {
+                    return Ok(Vec::new());
+                }
+                let i = Complex64::i();
+                let one = Complex64::new(1.0, 0.0);
+                let zero = Complex64::new(0.0, 0.0);
+                let paulis = [
+                    (1.0 - dist.x - dist.y - dist.z, [[one, zero], [zero, one]]),
+                    (dist.x, [[zero, one], [one, zero]]),
+                    (dist.y, [[zero, -i], [i, zero]]),
+                    (dist.z, [[one, zero], [zero, -one]]),
+                ];
+                paulis
+                    .iter()
+                    .filter(|(p, _)| *p > 0.0)
+                    .map(|(p, pauli)| pauli.map(|row| row.map(|v| v * p.sqrt())))
+                    .collect()
+            }
+            Noise::AmplitudeDamping { gamma } => {
+                let gamma = probability(*gamma, "gamma")?;
+                vec![
+                    real([[1.0, 0.0], [0.0, (1.0 - gamma).sqrt()]]),
+                    real([[0.0, gamma.sqrt()], [0.0, 0.0]]),
+                ]
+            }
+            Noise::PhaseDamping { lambda } => {
+                let lambda = probability(*lambda, "lambda")?;
+                vec![
+                    real([[1.0, 0.0], [0.0, (1.0 - lambda).sqrt()]]),
+                    real([[0.0, 0.0], [0.0, lambda.sqrt()]]),
+                ]
+            }
+            Noise::ThermalRelaxation { t1, t2, gate_time } => {
+                if *t1 <= 0.0 || *t2 <= 0.0 || *gate_time < 0.0 {
+                    return Err(invalid("`t1` and `t2` must be positive and `gate_time` non-negative"));
+                }
+                if *t2 > 2.0 * t1 {
+                    return Err(invalid("`t2` must not exceed 2 * `t1`"));
+                }
+                // T2 includes the dephasing caused by T1, the rest is pure dephasing
+                let gamma = 1.0 - (-gate_time / t1).exp();
+                let lambda = 1.0 - (-2.0 * gate_time / t2 + gate_time / t1).exp();
+                let damping = Noise::AmplitudeDamping { gamma }.kraus_operators()?;
+                let dephasing = Noise::PhaseDamping { lambda: lambda.max(0.0) }.kraus_operators()?;
+                dephasing
+                    .iter()
+                    .flat_map(|d| damping.iter().map(move |a| multiply(d, a)))
+                    .collect()
+            }
+            Noise::Kraus { operators } => {
+                let operators = operators
+                    .iter()
+                    .map(KrausOperator::to_matrix)
+                    .collect::<Result<Vec<_>, _>>()?;
+                // the channel must preserve the trace: sum of K^dagger K is the identity
+                for row in 0..2 {
+                    for column in 0..2 {
+                        let sum: Complex64 = operators
+                            .iter()
+                            .map(|k| (0..2).map(|j| k[j][row].conj() * k[j][column]).sum::<Complex64>())
+                            .sum();
+                        let expected = if row == column { 1.0 } else { 0.0 };
+                        if (sum - expected).norm() > 1e-6 {
+                            return Err(invalid("Kraus operators must satisfy sum of K^dagger K = I"));
+                        }
+                    }
+                }
+                operators
+            }
+        };
+        Ok(operators)
+    }
+}
+
+fn multiply(a: &Matrix2, b: &Matrix2) -> Matrix2 {
+    let mut product = [[Complex64::new(0.0, 0.0); 2]; 2];
+    for (row, values) in product.iter_mut().enumerate() {
+        for (column, value) in values.iter_mut().enumerate() {
+            *value = a[row][0] * b[0][column] + a[row][1] * b[1][column];
+        }
+    }
+    product
+}
+
+/// A 2x2 Kraus operator in row-major order.
+#[derive(Debug, Clone)]
+pub struct KrausOperator {
+    pub real: Vec<f64>,
+    pub imaginary: Vec<f64>,
+}
+
+impl KrausOperator {
+    fn to_matrix(&self) -> Result<Matrix2, QsError> {
+        if self.real.len() != 4 || self.imaginary.len() != 4 {
+            return Err(QsError::ErrorMessage {
+                error_text: "Invalid Kraus operator: expected 4 real and 4 imaginary parts".to_string(),
+            });
         }
+        let entry = |index: usize| Complex64::new(self.real[index], self.imaginary[index]);
+        Ok([[entry(0), entry(1)], [entry(2), entry(3)]])
     }
 }
 
@@ -54,3 +206,275 @@ impl PauliNoiseDistribution {
         Ok(Self { x, y, z })
     }
 }
+
+/// Noise applied after a two-qubit gate.
+#[derive(Debug, Clone)]
+pub enum TwoQubitNoise {
+    /// The same single-qubit channel on each of the two qubits, independently.
+    Independent { noise: Noise },
+    /// Correlated Pauli errors keyed by two-letter Pauli strings such as `XZ`, first letter for the first qubit.
+    Correlated { probabilities: HashMap<String, f64> },
+}
+
+/// Noise channels per gate kind. Gates without their own channel use the `noise` of the execution options.
+#[derive(Debug, Clone, Default)]
+pub struct NoiseModel {
+    pub x: Option<Noise>,
+    pub y: Option<Noise>,
+    pub z: Option<Noise>,
+    pub h: Option<Noise>,
+    pub s: Option<Noise>,
+    pub sadj: Option<Noise>,
+    pub t: Option<Noise>,
+    pub tadj: Option<Noise>,
+    pub rx: Option<Noise>,
+    pub ry: Option<Noise>,
+    pub rz: Option<Noise>,
+    pub cx: Option<TwoQubitNoise>,
+    pub cy: Option<TwoQubitNoise>,
+    pub cz: Option<TwoQubitNoise>,
+    pub swap: Option<TwoQubitNoise>,
+    pub rxx: Option<TwoQubitNoise>,
+    pub ryy: Option<TwoQubitNoise>,
+    pub rzz: Option<TwoQubitNoise>,
+    /// Applied to each of the three qubits.
+    pub ccx: Option<Noise>,
+    /// Applied before the measurement.
+    pub mz: Option<Noise>,
+    /// Applied before the measurement.
+    pub mresetz: Option<Noise>,
+    pub reset: Option<Noise>,
+}
+
+impl NoiseModel {
+    /// Checks every configured channel.
+    pub fn validate(&self) -> Result<(), QsError> {
+        for (_, noise) in self.single_qubit_channels() {
+            if let Some(noise) = noise {
+                noise.kraus_operators()?;
+            }
+        }
+
+        for (_, noise) in self.two_qubit_channels() {
+            match noise {
+                Some(TwoQubitNoise::Independent { noise }) => noise.kraus_operators().map(|_| ())?,
+                Some(correlated) => correlated.to_distribution().map(|_| ())?,
+                None => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// The single-qubit gate channels by gate name.
+    pub(crate) fn single_qubit_channels(&self) -> [(&'static str, &Option<Noise>); 15] {
+        [
+            ("x", &self.x),
+            ("y", &self.y),
+            ("z", &self.z),
+            ("h", &self.h),
+            ("s", &self.s),
+            ("sadj", &self.sadj),
+            ("t", &self.t),
+            ("tadj", &self.tadj),
+            ("rx", &self.rx),
+            ("ry", &self.ry),
+            ("rz", &self.rz),
+            ("ccx", &self.ccx),
+            ("mz", &self.mz),
+            ("mresetz", &self.mresetz),
+            ("reset", &self.reset),
+        ]
+    }
+
+    pub(crate) fn single_qubit_channels_mut(&mut self) -> [(&'static str, &mut Option<Noise>); 15] {
+        [
+            ("x", &mut self.x),
+            ("y", &mut self.y),
+            ("z", &mut self.z),
+            ("h", &mut self.h),
+            ("s", &mut self.s),
+            ("sadj", &mut self.sadj),
+            ("t", &mut self.t),
+            ("tadj", &mut self.tadj),
+            ("rx", &mut self.rx),
+            ("ry", &mut self.ry),
+            ("rz", &mut self.rz),
+            ("ccx", &mut self.ccx),
+            ("mz", &mut self.mz),
+            ("mresetz", &mut self.mresetz),
+            ("reset", &mut self.reset),
+        ]
+    }
+
+    /// The two-qubit gate channels by gate name.
+    pub(crate) fn two_qubit_channels(&self) -> [(&'static str, &Option<TwoQubitNoise>); 7] {
+        [
+            ("cx", &self.cx),
+            ("cy", &self.cy),
+            ("cz", &self.cz),
+            ("swap", &self.swap),
+            ("rxx", &self.rxx),
+            ("ryy", &self.ryy),
+            ("rzz", &self.rzz),
+        ]
+    }
+
+    pub(crate) fn two_qubit_channels_mut(&mut self) -> [(&'static str, &mut Option<TwoQubitNoise>); 7] {
+        [
+            ("cx", &mut self.cx),
+            ("cy", &mut self.cy),
+            ("cz", &mut self.cz),
+            ("swap", &mut self.swap),
+            ("rxx", &mut self.rxx),
+            ("ryy", &mut self.ryy),
+            ("rzz", &mut self.rzz),
+        ]
+    }
+
+    /// Resolves the channel of every gate kind, using `fallback` for gates without their own channel.
+    pub(crate) fn gate_channels<S, T>(
+        &self,
+        fallback: &Noise,
+        single: impl Fn(&Noise) -> Result<S, QsError>,
+        two_qubit: impl Fn(&TwoQubitNoise) -> Result<T, QsError>,
+    ) -> Result<GateChannels<S, T>, QsError> {
+        let fallback_two_qubit = TwoQubitNoise::Independent {
+            noise: fallback.clone(),
+        };
+        self.channels(
+            |noise| single(noise.unwrap_or(fallback)),
+            |noise| two_qubit(noise.unwrap_or(&fallback_two_qubit)),
+        )
+    }
+
+    /// Converts the channel of every gate kind, `None` for gates without their own channel.
+    pub(crate) fn channels<S, T>(
+        &self,
+        single: impl Fn(Option<&Noise>) -> Result<S, QsError>,
+        two_qubit: impl Fn(Option<&TwoQubitNoise>) -> Result<T, QsError>,
+    ) -> Result<GateChannels<S, T>, QsError> {
+        let single = |noise: &Option<Noise>| single(noise.as_ref());
+        let two_qubit = |noise: &Option<TwoQubitNoise>| two_qubit(noise.as_ref());
+
+        Ok(GateChannels {
+            x: single(&self.x)?,
+            y: single(&self.y)?,
+            z: single(&self.z)?,
+            h: single(&self.h)?,
+            s: single(&self.s)?,
+            sadj: single(&self.sadj)?,
+            t: single(&self.t)?,
+            tadj: single(&self.tadj)?,
+            rx: single(&self.rx)?,
+            ry: single(&self.ry)?,
+            rz: single(&self.rz)?,
+            ccx: single(&self.ccx)?,
+            mz: single(&self.mz)?,
+            mresetz: single(&self.mresetz)?,
+            reset: single(&self.reset)?,
+            cx: two_qubit(&self.cx)?,
+            cy: two_qubit(&self.cy)?,
+            cz: two_qubit(&self.cz)?,
+            swap: two_qubit(&self.swap)?,
+            rxx: two_qubit(&self.rxx)?,
+            ryy: two_qubit(&self.ryy)?,
+            rzz: two_qubit(&self.rzz)?,
+        })
+    }
+}
+
+/// Noise on qubits that wait while other qubits are operated on, applied once per layer of the schedule.
+/// Qubits that wait at the end of the schedule get their noise when released or when the state is captured.
+#[derive(Debug, Clone)]
+pub struct IdleNoise {
+    /// A Pauli channel.
+    pub noise: Noise,
+    /// Probability of losing a waiting qubit.
+    pub loss: Option<f64>,
+}
+
+/// Everything known about the noise of a device, e.g. loaded from a calibration file.
+#[derive(Debug, Clone, Default)]
+pub struct DeviceNoiseModel {
+    /// Channel of every gate without its own entry in `gates`.
+    pub noise: Noise,
+    pub gates: Option<NoiseModel>,
+    pub readout_error: Option<ReadoutError>,
+    /// Readout errors keyed by qubit allocation index.
+    pub qubit_readout_errors: Option<HashMap<u64, ReadoutError>>,
+    pub qubit_loss: Option<f64>,
+    /// Replaces `noise` on the listed qubits, keyed by qubit allocation index.
+    pub qubit_noise: Option<HashMap<u64, Noise>>,
+    /// Replaces `qubit_loss` on the listed qubits, keyed by qubit allocation index.
+    pub qubit_losses: Option<HashMap<u64, f64>>,
+    pub idle_noise: Option<IdleNoise>,
+}
+
+/// The resolved channels of a noise model, in the representation a backend needs.
+pub(crate) struct GateChannels<S, T> {
+    pub(crate) x: S,
+    pub(crate) y: S,
+    pub(crate) z: S,
+    pub(crate) h: S,
+    pub(crate) s: S,
+    pub(crate) sadj: S,
+    pub(crate) t: S,
+    pub(crate) tadj: S,
+    pub(crate) rx: S,
+    pub(crate) ry: S,
+    pub(crate) rz: S,
+    pub(crate) ccx: S,
+    pub(crate) mz: S,
+    pub(crate) mresetz: S,
+    pub(crate) reset: S,
+    pub(crate) cx: T,
+    pub(crate) cy: T,
+    pub(crate) cz: T,
+    pub(crate) swap: T,
+    pub(crate) rxx: T,
+    pub(crate) ryy: T,
+    pub(crate) rzz: T,
+}
+
+impl TwoQubitNoise {
+    /// The probability of each two-qubit Pauli error, as pairs of `I`, `X`, `Y` and `Z`.
+    pub(crate) fn to_distribution(&self) -> Result<Vec<([char; 2], f64)>, QsError> {
+        let invalid = |reason: String| QsError::ErrorMessage {
+            error_text: format!("Invalid two-qubit Pauli distribution: {reason}"),
+        };
+
+        match self {
+            TwoQubitNoise::Independent { noise } => {
+                let dist = noise.to_distribution()?;
+                let single = [('I', 1.0 - dist.x - dist.y - dist.z), ('X', dist.x), ('Y', dist.y), ('Z', dist.z)];
+                Ok(single
+                    .iter()
+                    .flat_map(|&(first, p0)| single.iter().map(move |&(second, p1)| ([first, second], p0 * p1)))
+                    .filter(|&(paulis, p)| paulis != ['I', 'I'] && p > 0.0)
+                    .collect())
+            }
+            TwoQubitNoise::Correlated { probabilities } => {
+                let mut errors = Vec::new();
+                for (key, &p) in probabilities {
+                    let paulis: Vec<char> = key.to_ascii_uppercase().chars().collect();
+                    let [first, second] = paulis[..] else {
+                        return Err(invalid(format!("`{key}` is not a two-qubit Pauli string")));
+                    };
+                    if !"IXYZ".contains(first) || !"IXYZ".contains(second) || [first, second] == ['I', 'I'] {
+                        return Err(invalid(format!("`{key}` is not a two-qubit Pauli error")));
+                    }
+                    if !(0.0..=1.0).contains(&p) {
+                        return Err(invalid(format!("probability of `{key}` must be between 0 and 1")));
+                    }
+                    errors.push(([first, second], p));
+                }
+                if errors.iter().map(|(_, p)| p).sum::<f64>() > 1.0 {
+                    return Err(invalid("values must sum to <= 1.0".to_string()));
+                }
+                // stable order, so that seeded runs are reproducible
+                errors.sort_by_key(|&(paulis, _)| paulis);
+                Ok(errors)
+            }
+        }
+    }
+}
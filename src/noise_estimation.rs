@@ -0,0 +1,45 @@
+use crate::estimation::{CustomQubitParams, InstructionSet, QubitModel};
+use crate::noise::Noise;
+use crate::sim::QsError;
+
+// The simulators apply the channel of `Noise` after every gate and before every measurement,
+// on each of the qubits a gate acts on. The error rates below follow from that: a gate fails when
+// any Pauli error occurs, a measurement only when the error flips the measured bit.
+
+/// The qubit parameters of `base` with the gate and measurement error rates of `noise`.
+/// Times and the idle error rate are kept from `base`, which must be gate-based.
+pub fn qubit_params_from_noise(noise: Noise, base: QubitModel) -> Result<CustomQubitParams, QsError> {
+    base.validate()?;
+    let base = base.params();
+    if base.instruction_set != InstructionSet::GateBased {
+        return Err(QsError::InvalidParameter {
+            parameter: "base".to_string(),
+            reason: "the simulators only model gate-based qubits".to_string(),
+        });
+    }
+
+    let dist = noise.to_distribution()?;
+    let gate_error_rate = dist.x + dist.y + dist.z;
+    Ok(CustomQubitParams {
+        name: format!("{}_noise", base.name),
+        one_qubit_measurement_error_rate: dist.x + dist.y,
+        one_qubit_gate_error_rate: Some(gate_error_rate),
+        // independent errors on both qubits
+        two_qubit_gate_error_rate: Some(1.0 - (1.0 - gate_error_rate).powi(2)),
+        t_gate_error_rate: gate_error_rate,
+        ..base
+    })
+}
+
+/// A depolarizing channel with the one-qubit gate error rate of the model. Majorana qubits have no
+/// one-qubit gates, their channel flips a measurement with the one-qubit measurement error rate.
+pub fn noise_from_qubit_model(model: QubitModel) -> Result<Noise, QsError> {
+    model.validate()?;
+    let params = model.params();
+    let p = match params.one_qubit_gate_error_rate {
+        Some(p) => p,
+        // two of the three Pauli errors of a depolarizing channel flip the measured bit
+        None => (1.5 * params.one_qubit_measurement_error_rate).min(1.0),
+    };
+    Ok(Noise::Depolarizing { p })
+}
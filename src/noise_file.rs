@@ -0,0 +1,402 @@
+use std::collections::HashMap;
+
+use serde_json::{Map, Value, json};
+
+use crate::noise::{DeviceNoiseModel, IdleNoise, KrausOperator, Noise, NoiseModel, PauliNoiseDistribution, TwoQubitNoise};
+use crate::readout::ReadoutError;
+use crate::sim::QsError;
+
+// Calibration files describe a channel as a table tagged by `type`, for example
+// `{ "type": "depolarizing", "p": 0.001 }`. A device noise model is a table with the optional keys
+// `noise`, `gates`, `readout_error`, `qubit_readout_errors`, `qubit_loss`, `qubit_noise`, `qubit_losses`
+// and `idle_noise`.
+
+pub fn noise_from_json(text: &str) -> Result<Noise, QsError> {
+    noise_from_value(&parse_json(text)?, "noise")
+}
+
+pub fn noise_to_json(noise: Noise) -> String {
+    pretty(&noise_to_value(&noise))
+}
+
+pub fn noise_model_from_json(text: &str) -> Result<DeviceNoiseModel, QsError> {
+    model_from_value(&parse_json(text)?)
+}
+
+pub fn noise_model_to_json(model: DeviceNoiseModel) -> String {
+    pretty(&model_to_value(&model))
+}
+
+pub fn noise_model_from_toml(text: &str) -> Result<DeviceNoiseModel, QsError> {
+    let value: Value = toml::from_str(text).map_err(|error| QsError::ErrorMessage {
+        error_text: format!("Invalid TOML: {error}"),
+    })?;
+    model_from_value(&value)
+}
+
+pub fn noise_model_to_toml(model: DeviceNoiseModel) -> Result<String, QsError> {
+    let to_error = |error: &dyn std::fmt::Display| QsError::ErrorMessage {
+        error_text: format!("Noise model cannot be written as TOML: {error}"),
+    };
+    let value = toml::Value::try_from(model_to_value(&model)).map_err(|error| to_error(&error))?;
+    toml::to_string(&value).map_err(|error| to_error(&error))
+}
+
+fn parse_json(text: &str) -> Result<Value, QsError> {
+    serde_json::from_str(text).map_err(|error| QsError::ErrorMessage {
+        error_text: format!("Invalid JSON: {error}"),
+    })
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+fn invalid(path: &str, reason: impl Into<String>) -> QsError {
+    QsError::InvalidParameter {
+        parameter: path.to_string(),
+        reason: reason.into(),
+    }
+}
+
+/// Reports a validation error at the path of the offending entry.
+fn at(path: &str, error: QsError) -> QsError {
+    match error {
+        QsError::ErrorMessage { error_text } => invalid(path, error_text),
+        QsError::InvalidParameter { parameter, reason } => invalid(&format!("{path}.{parameter}"), reason),
+    }
+}
+
+/// The entries of a table, rejecting keys other than `allowed` so that typos do not go unnoticed.
+fn table<'a>(value: &'a Value, path: &str, allowed: &[&str]) -> Result<&'a Map<String, Value>, QsError> {
+    let table = value.as_object().ok_or_else(|| invalid(path, "expected a table"))?;
+    if let Some(key) = table.keys().find(|key| !allowed.contains(&key.as_str())) {
+        return Err(invalid(path, format!("unknown key `{key}`")));
+    }
+    Ok(table)
+}
+
+fn number(table: &Map<String, Value>, key: &str, path: &str) -> Result<f64, QsError> {
+    table
+        .get(key)
+        .ok_or_else(|| invalid(path, format!("missing `{key}`")))?
+        .as_f64()
+        .ok_or_else(|| invalid(&format!("{path}.{key}"), "expected a number"))
+}
+
+fn numbers(table: &Map<String, Value>, key: &str, path: &str) -> Result<Vec<f64>, QsError> {
+    let path = format!("{path}.{key}");
+    table
+        .get(key)
+        .and_then(Value::as_array)
+        .ok_or_else(|| invalid(&path, "expected a list of numbers"))?
+        .iter()
+        .map(|value| value.as_f64().ok_or_else(|| invalid(&path, "expected a list of numbers")))
+        .collect()
+}
+
+fn probability(table: &Map<String, Value>, key: &str, path: &str) -> Result<f64, QsError> {
+    let p = number(table, key, path)?;
+    if !(0.0..=1.0).contains(&p) {
+        return Err(invalid(&format!("{path}.{key}"), "must be between 0.0 and 1.0"));
+    }
+    Ok(p)
+}
+
+fn noise_type<'a>(value: &'a Value, path: &str) -> Result<&'a str, QsError> {
+    value
+        .get("type")
+        .ok_or_else(|| invalid(path, "missing `type`"))?
+        .as_str()
+        .ok_or_else(|| invalid(&format!("{path}.type"), "expected a string"))
+}
+
+fn noise_from_value(value: &Value, path: &str) -> Result<Noise, QsError> {
+    let noise = match noise_type(value, path)? {
+        "ideal" => {
+            table(value, path, &["type"])?;
+            Noise::Ideal
+        }
+        "pauli" => {
+            let entries = table(value, path, &["type", "x", "y", "z"])?;
+            let noise = PauliNoiseDistribution::new(
+                number(entries, "x", path)?,
+                number(entries, "y", path)?,
+                number(entries, "z", path)?,
+            )
+            .map_err(|error| at(path, error))?;
+            Noise::Pauli { noise }
+        }
+        kind @ ("bit_flip" | "phase_flip" | "depolarizing") => {
+            let p = probability(table(value, path, &["type", "p"])?, "p", path)?;
+            match kind {
+                "bit_flip" => Noise::BitFlip { p },
+                "phase_flip" => Noise::PhaseFlip { p },
+                _ => Noise::Depolarizing { p },
+            }
+        }
+        "amplitude_damping" => Noise::AmplitudeDamping {
+            gamma: number(table(value, path, &["type", "gamma"])?, "gamma", path)?,
+        },
+        "phase_damping" => Noise::PhaseDamping {
+            lambda: number(table(value, path, &["type", "lambda"])?, "lambda", path)?,
+        },
+        "thermal_relaxation" => {
+            let entries = table(value, path, &["type", "t1", "t2", "gate_time"])?;
+            Noise::ThermalRelaxation {
+                t1: number(entries, "t1", path)?,
+                t2: number(entries, "t2", path)?,
+                gate_time: number(entries, "gate_time", path)?,
+            }
+        }
+        "kraus" => {
+            let entries = table(value, path, &["type", "operators"])?;
+            let operators_path = format!("{path}.operators");
+            let operators = entries
+                .get("operators")
+                .and_then(Value::as_array)
+                .ok_or_else(|| invalid(&operators_path, "expected a list of operators"))?
+                .iter()
+                .enumerate()
+                .map(|(index, operator)| {
+                    let path = format!("{operators_path}[{index}]");
+                    let entries = table(operator, &path, &["real", "imaginary"])?;
+                    Ok(KrausOperator {
+                        real: numbers(entries, "real", &path)?,
+                        imaginary: numbers(entries, "imaginary", &path)?,
+                    })
+                })
+                .collect::<Result<Vec<_>, QsError>>()?;
+            Noise::Kraus { operators }
+        }
+        other => return Err(invalid(&format!("{path}.type"), format!("unknown noise type `{other}`"))),
+    };
+    noise.kraus_operators().map_err(|error| at(path, error))?;
+    Ok(noise)
+}
+
+fn two_qubit_noise_from_value(value: &Value, path: &str) -> Result<TwoQubitNoise, QsError> {
+    if noise_type(value, path)? != "correlated" {
+        return Ok(TwoQubitNoise::Independent {
+            noise: noise_from_value(value, path)?,
+        });
+    }
+
+    let entries = table(value, path, &["type", "probabilities"])?;
+    let probabilities_path = format!("{path}.probabilities");
+    let probabilities = entries
+        .get("probabilities")
+        .and_then(Value::as_object)
+        .ok_or_else(|| invalid(&probabilities_path, "expected a table of probabilities"))?
+        .iter()
+        .map(|(paulis, p)| {
+            p.as_f64()
+                .map(|p| (paulis.clone(), p))
+                .ok_or_else(|| invalid(&format!("{probabilities_path}.{paulis}"), "expected a number"))
+        })
+        .collect::<Result<HashMap<_, _>, QsError>>()?;
+    let noise = TwoQubitNoise::Correlated { probabilities };
+    noise.to_distribution().map_err(|error| at(path, error))?;
+    Ok(noise)
+}
+
+fn gates_from_value(value: &Value, path: &str) -> Result<NoiseModel, QsError> {
+    let mut model = NoiseModel::default();
+    let names: Vec<&str> = model
+        .single_qubit_channels()
+        .iter()
+        .map(|(name, _)| *name)
+        .chain(model.two_qubit_channels().iter().map(|(name, _)| *name))
+        .collect();
+    let entries = table(value, path, &names)?;
+
+    for (name, channel) in model.single_qubit_channels_mut() {
+        if let Some(value) = entries.get(name) {
+            *channel = Some(noise_from_value(value, &format!("{path}.{name}"))?);
+        }
+    }
+    for (name, channel) in model.two_qubit_channels_mut() {
+        if let Some(value) = entries.get(name) {
+            *channel = Some(two_qubit_noise_from_value(value, &format!("{path}.{name}"))?);
+        }
+    }
+    Ok(model)
+}
+
+fn readout_error_from_value(value: &Value, path: &str) -> Result<ReadoutError, QsError> {
+    let entries = table(value, path, &["p_one_given_zero", "p_zero_given_one"])?;
+    Ok(ReadoutError {
+        p_one_given_zero: probability(entries, "p_one_given_zero", path)?,
+        p_zero_given_one: probability(entries, "p_zero_given_one", path)?,
+    })
+}
+
+/// Entries keyed by qubit allocation index.
+fn qubit_table<T>(
+    value: &Value,
+    path: &str,
+    parse: impl Fn(&Value, &str) -> Result<T, QsError>,
+) -> Result<HashMap<u64, T>, QsError> {
+    value
+        .as_object()
+        .ok_or_else(|| invalid(path, "expected a table keyed by qubit index"))?
+        .iter()
+        .map(|(qubit, value)| {
+            let index = qubit
+                .parse::<u64>()
+                .map_err(|_| invalid(path, format!("`{qubit}` is not a qubit index")))?;
+            Ok((index, parse(value, &format!("{path}.{qubit}"))?))
+        })
+        .collect()
+}
+
+fn loss_from_value(value: &Value, path: &str) -> Result<f64, QsError> {
+    let loss = value.as_f64().ok_or_else(|| invalid(path, "expected a number"))?;
+    if !(0.0..=1.0).contains(&loss) {
+        return Err(invalid(path, "must be between 0.0 and 1.0"));
+    }
+    Ok(loss)
+}
+
+fn model_from_value(value: &Value) -> Result<DeviceNoiseModel, QsError> {
+    let entries = table(
+        value,
+        "model",
+        &[
+            "noise",
+            "gates",
+            "readout_error",
+            "qubit_readout_errors",
+            "qubit_loss",
+            "qubit_noise",
+            "qubit_losses",
+            "idle_noise",
+        ],
+    )?;
+
+    let mut model = DeviceNoiseModel::default();
+    if let Some(noise) = entries.get("noise") {
+        model.noise = noise_from_value(noise, "noise")?;
+    }
+    if let Some(gates) = entries.get("gates") {
+        model.gates = Some(gates_from_value(gates, "gates")?);
+    }
+    if let Some(readout_error) = entries.get("readout_error") {
+        model.readout_error = Some(readout_error_from_value(readout_error, "readout_error")?);
+    }
+    if let Some(errors) = entries.get("qubit_readout_errors") {
+        model.qubit_readout_errors = Some(qubit_table(errors, "qubit_readout_errors", readout_error_from_value)?);
+    }
+    if let Some(qubit_loss) = entries.get("qubit_loss") {
+        model.qubit_loss = Some(loss_from_value(qubit_loss, "qubit_loss")?);
+    }
+    if let Some(qubit_noise) = entries.get("qubit_noise") {
+        model.qubit_noise = Some(qubit_table(qubit_noise, "qubit_noise", noise_from_value)?);
+    }
+    if let Some(losses) = entries.get("qubit_losses") {
+        model.qubit_losses = Some(qubit_table(losses, "qubit_losses", loss_from_value)?);
+    }
+    if let Some(idle_noise) = entries.get("idle_noise") {
+        let idle_entries = table(idle_noise, "idle_noise", &["noise", "loss"])?;
+        let noise = idle_entries
+            .get("noise")
+            .ok_or_else(|| invalid("idle_noise", "missing `noise`"))?;
+        let noise = noise_from_value(noise, "idle_noise.noise")?;
+        noise.to_distribution().map_err(|error| at("idle_noise.noise", error))?;
+        model.idle_noise = Some(IdleNoise {
+            noise,
+            loss: match idle_entries.get("loss") {
+                Some(loss) => Some(loss_from_value(loss, "idle_noise.loss")?),
+                None => None,
+            },
+        });
+    }
+    Ok(model)
+}
+
+fn noise_to_value(noise: &Noise) -> Value {
+    match noise {
+        Noise::Ideal => json!({ "type": "ideal" }),
+        Noise::Pauli { noise } => json!({ "type": "pauli", "x": noise.x, "y": noise.y, "z": noise.z }),
+        Noise::BitFlip { p } => json!({ "type": "bit_flip", "p": p }),
+        Noise::PhaseFlip { p } => json!({ "type": "phase_flip", "p": p }),
+        Noise::Depolarizing { p } => json!({ "type": "depolarizing", "p": p }),
+        Noise::AmplitudeDamping { gamma } => json!({ "type": "amplitude_damping", "gamma": gamma }),
+        Noise::PhaseDamping { lambda } => json!({ "type": "phase_damping", "lambda": lambda }),
+        Noise::ThermalRelaxation { t1, t2, gate_time } => {
+            json!({ "type": "thermal_relaxation", "t1": t1, "t2": t2, "gate_time": gate_time })
+        }
+        Noise::Kraus { operators } => json!({
+            "type": "kraus",
+            "operators": operators
+                .iter()
+                .map(|operator| json!({ "real": operator.real, "imaginary": operator.imaginary }))
+                .collect::<Vec<_>>(),
+        }),
+    }
+}
+
+fn two_qubit_noise_to_value(noise: &TwoQubitNoise) -> Value {
+    match noise {
+        TwoQubitNoise::Independent { noise } => noise_to_value(noise),
+        TwoQubitNoise::Correlated { probabilities } => {
+            json!({ "type": "correlated", "probabilities": probabilities })
+        }
+    }
+}
+
+fn readout_error_to_value(error: &ReadoutError) -> Value {
+    json!({ "p_one_given_zero": error.p_one_given_zero, "p_zero_given_one": error.p_zero_given_one })
+}
+
+fn qubit_table_to_value<T>(entries: &HashMap<u64, T>, to_value: impl Fn(&T) -> Value) -> Value {
+    let mut qubits: Vec<_> = entries.iter().collect();
+    qubits.sort_by_key(|(qubit, _)| **qubit);
+    Value::Object(qubits.into_iter().map(|(qubit, entry)| (qubit.to_string(), to_value(entry))).collect())
+}
+
+fn model_to_value(model: &DeviceNoiseModel) -> Value {
+    let mut entries = Map::new();
+    entries.insert("noise".to_string(), noise_to_value(&model.noise));
+    if let Some(gates) = &model.gates {
+        let mut channels = Map::new();
+        for (name, noise) in gates.single_qubit_channels() {
+            if let Some(noise) = noise {
+                channels.insert(name.to_string(), noise_to_value(noise));
+            }
+        }
+        for (name, noise) in gates.two_qubit_channels() {
+            if let Some(noise) = noise {
+                channels.insert(name.to_string(), two_qubit_noise_to_value(noise));
+            }
+        }
+        entries.insert("gates".to_string(), Value::Object(channels));
+    }
+    if let Some(readout_error) = &model.readout_error {
+        entries.insert("readout_error".to_string(), readout_error_to_value(readout_error));
+    }
+    if let Some(errors) = &model.qubit_readout_errors {
+        entries.insert(
+            "qubit_readout_errors".to_string(),
+            qubit_table_to_value(errors, readout_error_to_value),
+        );
+    }
+    if let Some(qubit_loss) = model.qubit_loss {
+        entries.insert("qubit_loss".to_string(), json!(qubit_loss));
+    }
+    if let Some(qubit_noise) = &model.qubit_noise {
+        entries.insert("qubit_noise".to_string(), qubit_table_to_value(qubit_noise, noise_to_value));
+    }
+    if let Some(losses) = &model.qubit_losses {
+        entries.insert("qubit_losses".to_string(), qubit_table_to_value(losses, |loss| json!(loss)));
+    }
+    if let Some(idle_noise) = &model.idle_noise {
+        let mut idle_entries = Map::new();
+        idle_entries.insert("noise".to_string(), noise_to_value(&idle_noise.noise));
+        if let Some(loss) = idle_noise.loss {
+            idle_entries.insert("loss".to_string(), json!(loss));
+        }
+        entries.insert("idle_noise".to_string(), Value::Object(idle_entries));
+    }
+    Value::Object(entries)
+}
@@ -0,0 +1,468 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use num_bigint::BigUint;
+use num_complex::Complex;
+use qsc::Backend;
+use qsc_eval::val::{self, Value};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::noise::{GateChannels, Noise, NoiseModel, TwoQubitNoise};
+use crate::readout::ReadoutError;
+use crate::sim::{ExecutionOptions, QsError, SimulatorBackend};
+
+#[derive(Clone, Copy, Default)]
+struct PauliChannel {
+    x: f64,
+    y: f64,
+    z: f64,
+}
+
+type TwoQubitChannel = Vec<([char; 2], f64)>;
+
+fn pauli_channel(noise: &Noise) -> Result<PauliChannel, QsError> {
+    let dist = noise.to_distribution()?;
+    Ok(PauliChannel {
+        x: dist.x,
+        y: dist.y,
+        z: dist.z,
+    })
+}
+
+#[derive(Clone, Copy)]
+struct IdleChannel {
+    noise: PauliChannel,
+    loss: f64,
+}
+
+type Channels = GateChannels<Option<PauliChannel>, Option<TwoQubitChannel>>;
+
+/// Wraps a backend and applies the noise of the execution options it cannot model itself:
+/// the channels of a `NoiseModel` and per-qubit noise after each gate, idle noise, qubit loss and
+/// readout errors on measurement results.
+pub(crate) struct NoisyBackend<B: Backend> {
+    sim: B,
+    /// The channels of the gates with their own entry in the noise model.
+    channels: Channels,
+    noise: PauliChannel,
+    qubit_noise: HashMap<usize, PauliChannel>,
+    loss: f64,
+    qubit_losses: HashMap<usize, f64>,
+    lost: HashSet<usize>,
+    lost_in_shot: BTreeSet<usize>,
+    idle: Option<IdleChannel>,
+    /// The layer of the last operation on each qubit, operations are scheduled as soon as possible.
+    layers: HashMap<usize, u64>,
+    depth: u64,
+    readout_error: Option<ReadoutError>,
+    qubit_readout_errors: HashMap<usize, ReadoutError>,
+    rng: StdRng,
+}
+
+impl<B: Backend> NoisyBackend<B> {
+    /// Without `gate_noise` the gate noise is left to the wrapped backend.
+    pub(crate) fn new(sim: B, options: &ExecutionOptions, gate_noise: bool) -> Result<Self, QsError> {
+        let (channels, noise, qubit_noise) = if gate_noise {
+            let default_model = NoiseModel::default();
+            let model = options.noise_model.as_ref().unwrap_or(&default_model);
+            let channels = model.channels(
+                |noise| noise.map(pauli_channel).transpose(),
+                |noise| noise.map(TwoQubitNoise::to_distribution).transpose(),
+            )?;
+            let qubit_noise = options
+                .qubit_noise
+                .iter()
+                .map(|(&qubit, noise)| Ok((qubit as usize, pauli_channel(noise)?)))
+                .collect::<Result<_, QsError>>()?;
+            (channels, pauli_channel(&options.noise)?, qubit_noise)
+        } else {
+            let channels = NoiseModel::default().channels(|_| Ok(None), |_| Ok(None))?;
+            (channels, PauliChannel::default(), HashMap::new())
+        };
+
+        let idle = match &options.idle_noise {
+            Some(idle_noise) => Some(IdleChannel {
+                noise: pauli_channel(&idle_noise.noise).map_err(|_| QsError::InvalidParameter {
+                    parameter: "idle_noise".to_string(),
+                    reason: "idle noise must be a Pauli channel".to_string(),
+                })?,
+                loss: idle_noise.loss.unwrap_or(0.0),
+            }),
+            None => None,
+        };
+
+        let idle_loss = idle.iter().map(|idle| &idle.loss);
+        let losses = options.qubit_loss.iter().chain(options.qubit_losses.values()).chain(idle_loss);
+        for loss in losses {
+            if !(0.0..=1.0).contains(loss) {
+                return Err(QsError::InvalidParameter {
+                    parameter: "qubit_loss".to_string(),
+                    reason: "loss probabilities must be between 0.0 and 1.0".to_string(),
+                });
+            }
+        }
+
+        let readout_errors = options.readout_error.iter().chain(options.qubit_readout_errors.values());
+        for readout_error in readout_errors {
+            readout_error.validate()?;
+        }
+
+        Ok(Self {
+            sim,
+            channels,
+            noise,
+            qubit_noise,
+            loss: options.qubit_loss.unwrap_or(0.0),
+            qubit_losses: options
+                .qubit_losses
+                .iter()
+                .map(|(&qubit, &loss)| (qubit as usize, loss))
+                .collect(),
+            lost: HashSet::new(),
+            lost_in_shot: BTreeSet::new(),
+            idle,
+            layers: HashMap::new(),
+            depth: 0,
+            readout_error: options.readout_error,
+            qubit_readout_errors: options
+                .qubit_readout_errors
+                .iter()
+                .map(|(&qubit, &readout_error)| (qubit as usize, readout_error))
+                .collect(),
+            rng: StdRng::from_entropy(),
+        })
+    }
+
+    fn read(&mut self, q: usize, result: val::Result) -> val::Result {
+        let val::Result::Val(bit) = result else {
+            return result;
+        };
+        let Some(readout_error) = self.qubit_readout_errors.get(&q).or(self.readout_error.as_ref()) else {
+            return result;
+        };
+
+        let p_flip = if bit {
+            readout_error.p_zero_given_one
+        } else {
+            readout_error.p_one_given_zero
+        };
+        val::Result::Val(bit ^ (self.rng.r#gen::<f64>() < p_flip))
+    }
+
+    /// The channel of a gate on `q`, the noise of the qubit if the gate has no channel of its own.
+    fn channel(&self, q: usize, gate: fn(&Channels) -> Option<PauliChannel>) -> PauliChannel {
+        gate(&self.channels).unwrap_or_else(|| self.qubit_noise.get(&q).copied().unwrap_or(self.noise))
+    }
+
+    /// Applies a gate unless one of its qubits is lost, then its noise and the loss of its qubits.
+    fn gate(&mut self, qubits: &[usize], gate: fn(&Channels) -> Option<PauliChannel>, apply_gate: impl FnOnce(&mut B)) {
+        self.schedule(qubits);
+        if qubits.iter().any(|q| self.lost.contains(q)) {
+            return;
+        }
+        apply_gate(&mut self.sim);
+        for &q in qubits {
+            let channel = self.channel(q, gate);
+            apply(&mut self.sim, &mut self.rng, channel, q);
+        }
+        self.apply_loss(qubits);
+    }
+
+    fn two_qubit_gate(
+        &mut self,
+        q0: usize,
+        q1: usize,
+        gate: fn(&Channels) -> &Option<TwoQubitChannel>,
+        apply_gate: impl FnOnce(&mut B),
+    ) {
+        self.schedule(&[q0, q1]);
+        if self.lost.contains(&q0) || self.lost.contains(&q1) {
+            return;
+        }
+        apply_gate(&mut self.sim);
+        match gate(&self.channels) {
+            Some(channel) => apply_two(&mut self.sim, &mut self.rng, channel, q0, q1),
+            // without a channel of its own, each qubit suffers its own noise independently
+            None => {
+                for q in [q0, q1] {
+                    let channel = self.qubit_noise.get(&q).copied().unwrap_or(self.noise);
+                    apply(&mut self.sim, &mut self.rng, channel, q);
+                }
+            }
+        }
+        self.apply_loss(&[q0, q1]);
+    }
+
+    fn apply_loss(&mut self, qubits: &[usize]) {
+        for &q in qubits {
+            let loss = self.qubit_losses.get(&q).copied().unwrap_or(self.loss);
+            self.lose(q, loss);
+        }
+    }
+
+    fn lose(&mut self, q: usize, loss: f64) {
+        if loss > 0.0 && !self.lost.contains(&q) && self.rng.r#gen::<f64>() < loss {
+            // the lost qubit leaves the register, it is replaced by a fresh qubit on reset
+            self.sim.mresetz(q);
+            self.lost.insert(q);
+            self.lost_in_shot.insert(q);
+        }
+    }
+
+    /// Schedules an operation on `qubits` in the first layer after their previous operations, and
+    /// applies the idle noise to each of them once for every layer it waited in.
+    fn schedule(&mut self, qubits: &[usize]) {
+        let Some(idle) = self.idle else {
+            return;
+        };
+        let layer = qubits.iter().map(|q| self.layers.get(q).copied().unwrap_or(0)).max().unwrap_or(0) + 1;
+        for &q in qubits {
+            self.wait(q, idle, layer);
+            self.layers.insert(q, layer);
+        }
+        self.depth = self.depth.max(layer);
+    }
+
+    /// Applies the idle noise to `q` once for every layer between its last operation and `layer`.
+    fn wait(&mut self, q: usize, idle: IdleChannel, layer: u64) {
+        for _ in self.layers.get(&q).copied().unwrap_or(0) + 1..layer {
+            if self.lost.contains(&q) {
+                break;
+            }
+            apply(&mut self.sim, &mut self.rng, idle.noise, q);
+            self.lose(q, idle.loss);
+        }
+    }
+
+    /// Applies the idle noise of the layers a qubit waited in until the end of the current schedule.
+    fn wait_until_depth(&mut self, q: usize) {
+        if let Some(idle) = self.idle {
+            self.wait(q, idle, self.depth + 1);
+            self.layers.insert(q, self.depth);
+        }
+    }
+
+    fn measure(
+        &mut self,
+        q: usize,
+        gate: fn(&Channels) -> Option<PauliChannel>,
+        measure: impl FnOnce(&mut B) -> val::Result,
+    ) -> val::Result {
+        self.schedule(&[q]);
+        if self.lost.contains(&q) {
+            return val::Result::Loss;
+        }
+        let channel = self.channel(q, gate);
+        apply(&mut self.sim, &mut self.rng, channel, q);
+        // the qubit can also get lost during the measurement itself
+        self.apply_loss(&[q]);
+        if self.lost.contains(&q) {
+            return val::Result::Loss;
+        }
+        let result = measure(&mut self.sim);
+        self.read(q, result)
+    }
+}
+
+fn apply_pauli(sim: &mut impl Backend, pauli: char, q: usize) {
+    match pauli {
+        'X' => sim.x(q),
+        'Y' => sim.y(q),
+        'Z' => sim.z(q),
+        _ => {}
+    }
+}
+
+fn apply(sim: &mut impl Backend, rng: &mut StdRng, channel: PauliChannel, q: usize) {
+    if channel.x + channel.y + channel.z == 0.0 {
+        return;
+    }
+    let r: f64 = rng.r#gen();
+    if r < channel.x {
+        sim.x(q);
+    } else if r < channel.x + channel.y {
+        sim.y(q);
+    } else if r < channel.x + channel.y + channel.z {
+        sim.z(q);
+    }
+}
+
+fn apply_two(sim: &mut impl Backend, rng: &mut StdRng, channel: &TwoQubitChannel, q0: usize, q1: usize) {
+    if channel.is_empty() {
+        return;
+    }
+    let mut r: f64 = rng.r#gen();
+    for &([first, second], p) in channel {
+        if r < p {
+            apply_pauli(sim, first, q0);
+            apply_pauli(sim, second, q1);
+            return;
+        }
+        r -= p;
+    }
+}
+
+impl<B: SimulatorBackend> SimulatorBackend for NoisyBackend<B> {
+    fn take_error(&mut self) -> Option<String> {
+        self.sim.take_error()
+    }
+
+    fn take_lost_qubits(&mut self) -> Vec<usize> {
+        let mut lost: BTreeSet<usize> = std::mem::take(&mut self.lost_in_shot);
+        lost.extend(self.sim.take_lost_qubits());
+        lost.into_iter().collect()
+    }
+}
+
+impl<B: Backend> Backend for NoisyBackend<B> {
+    fn ccx(&mut self, ctl0: usize, ctl1: usize, q: usize) {
+        self.gate(&[ctl0, ctl1, q], |c| c.ccx, |sim| sim.ccx(ctl0, ctl1, q));
+    }
+
+    fn cx(&mut self, ctl: usize, q: usize) {
+        self.two_qubit_gate(ctl, q, |c| &c.cx, |sim| sim.cx(ctl, q));
+    }
+
+    fn cy(&mut self, ctl: usize, q: usize) {
+        self.two_qubit_gate(ctl, q, |c| &c.cy, |sim| sim.cy(ctl, q));
+    }
+
+    fn cz(&mut self, ctl: usize, q: usize) {
+        self.two_qubit_gate(ctl, q, |c| &c.cz, |sim| sim.cz(ctl, q));
+    }
+
+    fn h(&mut self, q: usize) {
+        self.gate(&[q], |c| c.h, |sim| sim.h(q));
+    }
+
+    fn m(&mut self, q: usize) -> val::Result {
+        self.measure(q, |c| c.mz, |sim| sim.m(q))
+    }
+
+    fn mresetz(&mut self, q: usize) -> val::Result {
+        let result = self.measure(q, |c| c.mresetz, |sim| sim.mresetz(q));
+        self.lost.remove(&q);
+        result
+    }
+
+    fn reset(&mut self, q: usize) {
+        self.schedule(&[q]);
+        // a lost qubit is already replaced by a fresh one
+        if !self.lost.remove(&q) {
+            self.sim.reset(q);
+            let channel = self.channel(q, |c| c.reset);
+            apply(&mut self.sim, &mut self.rng, channel, q);
+        }
+    }
+
+    fn rx(&mut self, theta: f64, q: usize) {
+        self.gate(&[q], |c| c.rx, |sim| sim.rx(theta, q));
+    }
+
+    fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.two_qubit_gate(q0, q1, |c| &c.rxx, |sim| sim.rxx(theta, q0, q1));
+    }
+
+    fn ry(&mut self, theta: f64, q: usize) {
+        self.gate(&[q], |c| c.ry, |sim| sim.ry(theta, q));
+    }
+
+    fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.two_qubit_gate(q0, q1, |c| &c.ryy, |sim| sim.ryy(theta, q0, q1));
+    }
+
+    fn rz(&mut self, theta: f64, q: usize) {
+        self.gate(&[q], |c| c.rz, |sim| sim.rz(theta, q));
+    }
+
+    fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.two_qubit_gate(q0, q1, |c| &c.rzz, |sim| sim.rzz(theta, q0, q1));
+    }
+
+    fn sadj(&mut self, q: usize) {
+        self.gate(&[q], |c| c.sadj, |sim| sim.sadj(q));
+    }
+
+    fn s(&mut self, q: usize) {
+        self.gate(&[q], |c| c.s, |sim| sim.s(q));
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        self.two_qubit_gate(q0, q1, |c| &c.swap, |sim| sim.swap(q0, q1));
+    }
+
+    fn tadj(&mut self, q: usize) {
+        self.gate(&[q], |c| c.tadj, |sim| sim.tadj(q));
+    }
+
+    fn t(&mut self, q: usize) {
+        self.gate(&[q], |c| c.t, |sim| sim.t(q));
+    }
+
+    fn x(&mut self, q: usize) {
+        self.gate(&[q], |c| c.x, |sim| sim.x(q));
+    }
+
+    fn y(&mut self, q: usize) {
+        self.gate(&[q], |c| c.y, |sim| sim.y(q));
+    }
+
+    fn z(&mut self, q: usize) {
+        self.gate(&[q], |c| c.z, |sim| sim.z(q));
+    }
+
+    fn qubit_allocate(&mut self) -> usize {
+        let q = self.sim.qubit_allocate();
+        if self.idle.is_some() {
+            self.layers.insert(q, self.depth);
+        }
+        q
+    }
+
+    fn qubit_release(&mut self, q: usize) -> bool {
+        self.wait_until_depth(q);
+        self.lost.remove(&q);
+        self.layers.remove(&q);
+        if self.layers.is_empty() {
+            self.depth = 0;
+        }
+        self.sim.qubit_release(q)
+    }
+
+    fn qubit_swap_id(&mut self, q0: usize, q1: usize) {
+        self.sim.qubit_swap_id(q0, q1);
+        let (lost0, lost1) = (self.lost.remove(&q0), self.lost.remove(&q1));
+        if lost0 {
+            self.lost.insert(q1);
+        }
+        if lost1 {
+            self.lost.insert(q0);
+        }
+        let (layer0, layer1) = (self.layers.remove(&q0), self.layers.remove(&q1));
+        self.layers.extend(layer0.map(|layer| (q1, layer)));
+        self.layers.extend(layer1.map(|layer| (q0, layer)));
+    }
+
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        // sorted, so that a seeded run draws the noise in the same order
+        let mut qubits: Vec<usize> = self.layers.keys().copied().collect();
+        qubits.sort_unstable();
+        for q in qubits {
+            self.wait_until_depth(q);
+        }
+        self.sim.capture_quantum_state()
+    }
+
+    fn qubit_is_zero(&mut self, q: usize) -> bool {
+        self.sim.qubit_is_zero(q)
+    }
+
+    fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
+        self.sim.custom_intrinsic(name, arg)
+    }
+
+    fn set_seed(&mut self, seed: Option<u64>) {
+        self.sim.set_seed(seed);
+        self.rng = seed.map_or_else(StdRng::from_entropy, StdRng::seed_from_u64);
+    }
+}
@@ -1,15 +1,33 @@
-use qsc::{
-    LanguageFeatures, PackageType, SourceMap,
-    interpret::{CircuitEntryPoint, CircuitGenerationMethod, Interpreter}, target::Profile,
-};
-use qsc_circuit::{Circuit, Operation, TracerConfig};
+use qsc::interpret::CircuitEntryPoint;
+use qsc_circuit::{Circuit, Operation};
 use std::collections::HashMap;
+use std::ops::Range;
 
+use crate::circuit::{generate_circuit, qubit_variable_names, top_level_columns, CircuitGenerationOptions};
 use crate::sim::QsError;
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct QuantikzGenerationOptions {
+    /// Groups operations by the Q# operation they were called in. Only used without `circuit`.
     pub group_by_scope: bool,
+    /// How the circuit is traced. Defaults to the tracer defaults with `group_by_scope`.
+    pub circuit: Option<CircuitGenerationOptions>,
+    /// Wraps the circuit in a compilable `standalone` LaTeX document.
+    pub standalone: bool,
+    /// Folds the circuit into stacked rows of at most this many columns.
+    pub max_columns: Option<u32>,
+    /// Quantikz `row sep` value, e.g. `0.8cm`.
+    pub row_sep: Option<String>,
+    /// Quantikz `column sep` value, e.g. `0.4cm`.
+    pub column_sep: Option<String>,
+    /// Labels wires with the Q# variable names of the qubits, where the tracer reports their declarations.
+    /// This needs source locations; wires whose qubit cannot be named keep their id.
+    pub variable_labels: bool,
+    /// Wire labels keyed by qubit id, e.g. `\mathrm{anc}`. These take precedence over variable names,
+    /// which need `source_locations` in the circuit settings.
+    pub qubit_labels: Option<HashMap<u64, String>>,
+    /// Initial states keyed by qubit id, e.g. `+` for `\ket{+}`. Wires start in `\ket{0}` otherwise.
+    pub initial_states: Option<HashMap<u64, String>>,
 }
 
 type RegisterMap = HashMap<(usize, Option<usize>), usize>;
@@ -33,85 +51,78 @@ fn generate_quantikz_circuit(
     entry_point: CircuitEntryPoint,
     options: QuantikzGenerationOptions,
 ) -> Result<String, QsError> {
-    let sources = SourceMap::new([("test.qs".into(), source.into())], None);
-    let (std_id, store) = qsc::compile::package_store_with_stdlib(Profile::Unrestricted.into());
-
-    let mut interpreter = match Interpreter::with_circuit_trace(
-        sources,
-        PackageType::Exe,
-        Profile::Unrestricted.into(),
-        LanguageFeatures::default(),
-        store,
-        &[(std_id, None)],
-        Default::default(),
-    ) {
-        Ok(interpreter) => interpreter,
-        Err(errors) => {
-            return Err(errors.into());
+    let settings = options.circuit.clone().unwrap_or(CircuitGenerationOptions {
+        group_by_scope: options.group_by_scope,
+        ..Default::default()
+    });
+    let circuit = generate_circuit(source, entry_point, &settings)?;
+
+    let mut options = options;
+    if options.variable_labels {
+        let labels = options.qubit_labels.get_or_insert_with(HashMap::new);
+        for (id, name) in qubit_variable_names(&circuit, source) {
+            labels
+                .entry(id as u64)
+                .or_insert_with(|| escape_latex(&name));
         }
-    };
-
-    let circuit = interpreter.circuit(
-        entry_point,
-        CircuitGenerationMethod::ClassicalEval,
-        TracerConfig {
-            group_by_scope: options.group_by_scope,
-            ..Default::default()
-        },
-    )?;
+    }
 
-    Ok(circuit_to_quantikz(&circuit))
+    Ok(circuit_to_quantikz_with_options(&circuit, &options))
 }
 
 pub fn circuit_to_quantikz(c: &Circuit) -> String {
-    let (mut rows, register_to_row) = build_rows(c);
-    let grid = &c.component_grid;
-
-    // Check if we have a single top-level operation that wraps the entire circuit.
-    // If so, we unwrap it and use its children grid.
-    let maybe_children = if grid.len() == 1 && grid[0].components.len() == 1 {
-        match &grid[0].components[0] {
-            Operation::Unitary(u) => Some(&u.children),
-            Operation::Measurement(m) => Some(&m.children),
-            _ => None,
-        }
-    } else {
-        None
-    };
+    circuit_to_quantikz_with_options(c, &QuantikzGenerationOptions::default())
+}
 
-    let columns: Vec<Vec<&Operation>> = maybe_children
-        .filter(|children| !children.is_empty())
-        .map(|children| children.iter().map(|c| c.components.iter().collect()).collect())
-        .unwrap_or_else(|| grid.iter().map(|c| c.components.iter().collect()).collect());
+pub fn circuit_to_quantikz_with_options(c: &Circuit, options: &QuantikzGenerationOptions) -> String {
+    let (rows, register_to_row) = build_rows(c, options);
+    let columns = top_level_columns(c);
 
     let col_count = columns.len();
     let mut table = initialize_table(rows.len(), col_count, &rows);
 
-    populate_table(&columns, &register_to_row, &mut table, &mut rows);
+    populate_table(&columns, &register_to_row, &mut table, &rows);
 
-    render_latex(&rows, &table)
+    render_latex(&rows, &table, options)
 }
 
-fn build_rows(c: &Circuit) -> (Vec<Row>, RegisterMap) {
+fn build_rows(c: &Circuit, options: &QuantikzGenerationOptions) -> (Vec<Row>, RegisterMap) {
     let mut rows: Vec<Row> = Vec::new();
     let mut register_to_row = HashMap::new();
 
     for q in &c.qubits {
-        let label = format!("\\lstick{{$\\ket{{0}}_{{{}}}$}}", q.id);
+        let id = q.id as u64;
+        let state = options
+            .initial_states
+            .as_ref()
+            .and_then(|states| states.get(&id))
+            .map_or("0", String::as_str);
+        let name = options
+            .qubit_labels
+            .as_ref()
+            .and_then(|labels| labels.get(&id))
+            .cloned()
+            .unwrap_or_else(|| q.id.to_string());
+        let label = format!("\\lstick{{$\\ket{{{}}}_{{{}}}$}}", state, name);
         let row_idx = rows.len();
         
         // Map the Qubit ID to this row
         register_to_row.insert((q.id, None), row_idx);
 
-        // Map all result registers associated with this qubit to the SAME row.
-        for i in 0..q.num_results {
-             register_to_row.insert((q.id, Some(i)), row_idx);
-        }
-
         rows.push(Row {
             label: Some(label),
             is_classical: false,
         });
+
+        // Each result register of this qubit gets its own classical row right below it.
+        // The wire stays hidden until the measurement that produces the result.
+        for i in 0..q.num_results {
+            register_to_row.insert((q.id, Some(i)), rows.len());
+            rows.push(Row {
+                label: Some(format!("\\lstick{{$c_{{{},{}}}$}}\\setwiretype{{n}}", q.id, i)),
+                is_classical: true,
+            });
+        }
     }
     (rows, register_to_row)
 }
@@ -121,7 +132,7 @@ fn initialize_table(row_count: usize, col_count: usize, rows: &[Row]) -> Vec<Vec
     for (r_idx, row) in rows.iter().enumerate() {
         for c_idx in 0..=col_count {
             table[r_idx][c_idx] = if row.is_classical {
-                String::from("\\cw")
+                String::new()
             } else {
                 String::from("\\qw")
             };
@@ -134,7 +145,7 @@ fn populate_table(
     columns: &[Vec<&Operation>],
     register_to_row: &RegisterMap,
     table: &mut [Vec<String>],
-    rows: &mut [Row],
+    rows: &[Row],
 ) {
     for (col_index, col) in columns.iter().enumerate() {
         let table_col = col_index;
@@ -142,8 +153,9 @@ fn populate_table(
             // For measurements, we want to draw on the qubit line, so we treat qubits as targets for visual placement
             let targets = get_rows_for_operation(op, register_to_row, true); 
             let controls = get_rows_for_operation(op, register_to_row, false);
+            let results = get_result_rows_for_operation(op, register_to_row);
 
-            process_operation(op, table_col, &targets, &controls, table, rows);
+            process_operation(op, table_col, &targets, &controls, &results, table, rows);
         }
     }
 }
@@ -153,13 +165,14 @@ fn process_operation(
     col: usize,
     targets: &[usize],
     controls: &[usize],
+    results: &[usize],
     table: &mut [Vec<String>],
-    rows: &mut [Row],
+    rows: &[Row],
 ) {
     match op {
         Operation::Unitary(u) => {
             if !u.children.is_empty() {
-                 process_group(&u.gate, &u.args, col, targets, controls, table);
+                 process_group(&u.gate, &u.args, col, targets, controls, table, rows);
             } else {
                 process_unitary(
                     &u.gate,
@@ -169,19 +182,27 @@ fn process_operation(
                     targets,
                     controls,
                     table,
+                    rows,
                 );
             }
         }
         Operation::Measurement(m) => {
              if !m.children.is_empty() {
-                 process_group(&m.gate, &m.args, col, targets, controls, table);
+                 process_group(&m.gate, &m.args, col, targets, controls, table, rows);
             } else {
                 for &t in targets {
                     table[t][col] = String::from("\\meter{}");
-                    rows[t].is_classical = true;
-                    // Switch the rest of the wire to classical
-                    for next_c in (col + 1)..table[t].len() {
-                        table[t][next_c] = String::from("\\cw");
+                }
+                // Connect the meter to the classical rows holding its results
+                if let (Some(&last_target), Some(&last_result)) = (targets.last(), results.last()) {
+                    let offset = last_result as isize - last_target as isize;
+                    table[last_target][col].push_str(&format!(" \\vcw{{{}}}", offset));
+                }
+                for &r in results {
+                    table[r][col] = String::from("\\setwiretype{c}");
+                    // The classical wire starts at the measurement
+                    for next_c in (col + 1)..table[r].len() {
+                        table[r][next_c] = String::from("\\cw");
                     }
                 }
             }
@@ -189,11 +210,6 @@ fn process_operation(
         Operation::Ket(_) => {
             for &t in targets {
                 table[t][col] = String::from("\\gate{\\ket{0}}");
-                rows[t].is_classical = false;
-                // Switch the rest of the wire back to quantum
-                for next_c in (col + 1)..table[t].len() {
-                    table[t][next_c] = String::from("\\qw");
-                }
             }
         }
     }
@@ -206,6 +222,7 @@ fn process_group(
     targets: &[usize],
     controls: &[usize],
     table: &mut [Vec<String>],
+    rows: &[Row],
 ) {
     let simple_name = name.split('.').last().unwrap_or(name);
     let label = operation_label(simple_name, args, false);
@@ -218,11 +235,12 @@ fn process_group(
         // Add controls
         for &ctrl in controls {
             let offset = min_row as isize - ctrl as isize;
-            table[ctrl][col] = format!("\\ctrl{{{}}}", offset);
+            table[ctrl][col] = control_cell(offset, rows[ctrl].is_classical);
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn process_unitary(
     name: &str,
     args: &[String],
@@ -231,9 +249,16 @@ fn process_unitary(
     targets: &[usize],
     controls: &[usize],
     table: &mut [Vec<String>],
+    rows: &[Row],
 ) {
     let simple_name = name.split('.').last().unwrap_or(name);
     let is_swap = simple_name.eq_ignore_ascii_case("swap");
+    // Controls on classical result rows never turn the target into a quantum control symbol
+    let quantum_controls: Vec<usize> = controls
+        .iter()
+        .copied()
+        .filter(|&ctrl| !rows[ctrl].is_classical)
+        .collect();
 
     if is_swap && targets.len() == 2 {
         let t1 = targets[0];
@@ -244,54 +269,174 @@ fn process_unitary(
 
         for &ctrl in controls {
             let offset = t1 as isize - ctrl as isize;
-            table[ctrl][col] = format!("\\ctrl{{{}}}", offset);
+            table[ctrl][col] = control_cell(offset, rows[ctrl].is_classical);
         }
         return;
     }
 
-    if (simple_name == "X" || simple_name == "CNOT") && !controls.is_empty() {
+    if (simple_name == "X" || simple_name == "CNOT") && !quantum_controls.is_empty() {
         for &t in targets {
             table[t][col] = String::from("\\targ{}");
         }
-    } else if simple_name == "Z" && !controls.is_empty() {
+    } else if simple_name == "Z" && !quantum_controls.is_empty() {
         for &t in targets {
-            let ctrl_ref = controls[0];
+            let ctrl_ref = quantum_controls[0];
             let diff = t as isize - ctrl_ref as isize;
             table[t][col] = format!("\\ctrl{{{}}}", -diff);
         }
     } else {
         let label = operation_label(simple_name, args, is_adjoint);
-        if targets.len() > 1 {
-            let first_row = targets[0];
-            table[first_row][col] = format!("\\gate[wires={}]{{{}}}", targets.len(), label);
-        } else if targets.len() == 1 {
-            let r = targets[0];
-            table[r][col] = format!("\\gate{{{}}}", label);
+        if let (Some(&first_row), Some(&last_row)) = (targets.first(), targets.last()) {
+            if first_row == last_row {
+                table[first_row][col] = format!("\\gate{{{}}}", label);
+            } else {
+                let wires = last_row - first_row + 1;
+                table[first_row][col] = format!("\\gate[wires={}]{{{}}}", wires, label);
+            }
         }
     }
 
     if !controls.is_empty() {
         for &ctrl in controls {
-            let target = targets.get(0).copied().unwrap_or(ctrl);
+            let target = targets.first().copied().unwrap_or(ctrl);
             let offset = target as isize - ctrl as isize;
-            table[ctrl][col] = format!("\\ctrl{{{}}}", offset);
+            table[ctrl][col] = control_cell(offset, rows[ctrl].is_classical);
         }
     }
 }
 
-fn render_latex(rows: &[Row], table: &[Vec<String>]) -> String {
+fn control_cell(offset: isize, is_classical: bool) -> String {
+    if is_classical {
+        format!("\\control{{}} \\vcw{{{}}}", offset)
+    } else {
+        format!("\\ctrl{{{}}}", offset)
+    }
+}
+
+fn render_latex(rows: &[Row], table: &[Vec<String>], options: &QuantikzGenerationOptions) -> String {
+    let column_count = table.first().map_or(0, |cells| cells.len());
+    let segments = fold_columns(column_count, options.max_columns);
+
+    let mut body = String::new();
+    for (segment_idx, segment) in segments.iter().enumerate() {
+        if segment_idx > 0 {
+            body.push_str("\\par\\bigskip\n");
+        }
+        let is_continuation = segment_idx > 0;
+        let is_continued = segment_idx + 1 < segments.len();
+        render_segment(&mut body, rows, table, segment, is_continuation, is_continued, options);
+    }
+
+    if !options.standalone {
+        return body;
+    }
+
     let mut out = String::new();
-    out.push_str("\\begin{quantikz}\n");
+    if segments.len() > 1 {
+        out.push_str("\\documentclass[border=6pt,varwidth]{standalone}\n");
+    } else {
+        out.push_str("\\documentclass[border=6pt]{standalone}\n");
+    }
+    out.push_str("\\usepackage{tikz}\n");
+    out.push_str("\\usetikzlibrary{quantikz2}\n");
+    out.push_str("\\begin{document}\n");
+    out.push_str(&body);
+    out.push_str("\\end{document}\n");
+    out
+}
+
+fn render_segment(
+    out: &mut String,
+    rows: &[Row],
+    table: &[Vec<String>],
+    columns: &Range<usize>,
+    is_continuation: bool,
+    is_continued: bool,
+    options: &QuantikzGenerationOptions,
+) {
+    out.push_str("\\begin{quantikz}");
+    out.push_str(&environment_options(options));
+    out.push('\n');
     for (row_idx, row) in rows.iter().enumerate() {
-        if let Some(l) = &row.label {
+        if is_continuation {
+            // Classical rows whose result has not been measured yet still have no wire
+            if row.is_classical && table[row_idx][columns.start - 1].is_empty() {
+                out.push_str("\\setwiretype{n}");
+            } else {
+                out.push_str(WIRE_CONTINUATION);
+            }
+        } else if let Some(l) = &row.label {
             out.push_str(l);
         }
         out.push_str(" & ");
-        out.push_str(&table[row_idx].join(" & "));
+        out.push_str(&table[row_idx][columns.clone()].join(" & "));
+        if is_continued {
+            out.push_str(" & ");
+            if !(row.is_classical && table[row_idx][columns.end - 1].is_empty()) {
+                out.push_str(WIRE_CONTINUATION);
+            }
+        }
         out.push_str(" \\\\\n");
     }
     out.push_str("\\end{quantikz}\n");
-    out
+}
+
+const WIRE_CONTINUATION: &str = "\\ \\ldots\\ ";
+
+fn environment_options(options: &QuantikzGenerationOptions) -> String {
+    let mut settings = Vec::new();
+    if let Some(row_sep) = &options.row_sep {
+        settings.push(format!("row sep={{{}}}", row_sep));
+    }
+    if let Some(column_sep) = &options.column_sep {
+        settings.push(format!("column sep={{{}}}", column_sep));
+    }
+
+    if settings.is_empty() {
+        String::new()
+    } else {
+        format!("[{}]", settings.join(", "))
+    }
+}
+
+// Splits the table columns into segments of at most `max_columns` gate columns.
+// The trailing wire column always stays with the last segment.
+fn fold_columns(column_count: usize, max_columns: Option<u32>) -> Vec<Range<usize>> {
+    let gate_columns = column_count.saturating_sub(1);
+    let max_columns = max_columns
+        .filter(|&max_columns| max_columns > 0)
+        .map_or(usize::MAX, |max_columns| max_columns as usize);
+
+    let mut segments = Vec::new();
+    let mut start = 0;
+    while start < gate_columns {
+        let end = gate_columns.min(start.saturating_add(max_columns));
+        segments.push(start..end);
+        start = end;
+    }
+    match segments.last_mut() {
+        Some(last) => last.end = column_count,
+        None => segments.push(0..column_count),
+    }
+    segments
+}
+
+// Escapes the characters with a special meaning in LaTeX math mode.
+fn escape_latex(text: &str) -> String {
+    let mut escaped = String::new();
+    for ch in text.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\backslash "),
+            '^' => escaped.push_str("\\hat{}"),
+            '~' => escaped.push_str("\\sim "),
+            '_' | '{' | '}' | '#' | '$' | '%' | '&' => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
 }
 
 fn operation_label(name: &str, args: &[String], is_adjoint: bool) -> String {
@@ -351,4 +496,20 @@ fn get_rows_for_operation(
     rows.sort();
     rows.dedup(); // Remove duplicates if result/qubit mapped to same row
     rows
-}
\ No newline at end of file
+}
+
+fn get_result_rows_for_operation(op: &Operation, register_to_row: &RegisterMap) -> Vec<usize> {
+    let Operation::Measurement(m) = op else {
+        return Vec::new();
+    };
+
+    let mut rows: Vec<usize> = m
+        .results
+        .iter()
+        .filter_map(|reg| register_to_row.get(&(reg.qubit, reg.result)).copied())
+        .collect();
+
+    rows.sort();
+    rows.dedup();
+    rows
+}
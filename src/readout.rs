@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use crate::sim::{ExecutionState, MeasurementOutcome, QsError};
+
+/// Asymmetric measurement error of a single qubit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReadoutError {
+    /// Probability of reading `One` when the qubit is in the zero state.
+    pub p_one_given_zero: f64,
+    /// Probability of reading `Zero` when the qubit is in the one state.
+    pub p_zero_given_one: f64,
+}
+
+impl ReadoutError {
+    pub fn new(p_one_given_zero: f64, p_zero_given_one: f64) -> Result<Self, QsError> {
+        let error = Self {
+            p_one_given_zero,
+            p_zero_given_one,
+        };
+        error.validate()?;
+        Ok(error)
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), QsError> {
+        if !(0.0..=1.0).contains(&self.p_one_given_zero) || !(0.0..=1.0).contains(&self.p_zero_given_one) {
+            return Err(QsError::ErrorMessage {
+                error_text: "Invalid readout error: probabilities must be between 0.0 and 1.0".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Counts the measured bitstrings of the shots, e.g. `(One, Zero)` counts as `10`.
+/// Shots whose result contains no measurement results are skipped, and so are shots in which a
+/// measured qubit was lost, since their outcome is no bitstring; `loss_statistics` counts those.
+pub fn measurement_histogram(results: Vec<ExecutionState>) -> HashMap<String, u64> {
+    let mut histogram = HashMap::new();
+    for result in &results {
+        if let Some(bits) = outcome_bits(&result.outcomes) {
+            *histogram.entry(bits).or_insert(0) += 1;
+        }
+    }
+    histogram
+}
+
+fn outcome_bits(outcomes: &[MeasurementOutcome]) -> Option<String> {
+    if outcomes.is_empty() {
+        return None;
+    }
+    outcomes
+        .iter()
+        .map(|outcome| match outcome {
+            MeasurementOutcome::Zero => Some('0'),
+            MeasurementOutcome::One => Some('1'),
+            MeasurementOutcome::Loss => None,
+        })
+        .collect()
+}
+
+/// Builds the calibration matrix of independent per-bit readout errors, first bit most significant.
+/// Column `j` holds the probabilities of reading each bitstring when the bitstring `j` was prepared.
+pub fn readout_calibration_matrix(errors: Vec<ReadoutError>) -> Result<Vec<Vec<f64>>, QsError> {
+    let mut matrix = vec![vec![1.0]];
+    for error in &errors {
+        error.validate()?;
+        let bit = [
+            [1.0 - error.p_one_given_zero, error.p_zero_given_one],
+            [error.p_one_given_zero, 1.0 - error.p_zero_given_one],
+        ];
+        let size = matrix.len();
+        let mut next = vec![vec![0.0; size * 2]; size * 2];
+        for (row, values) in matrix.iter().enumerate() {
+            for (column, value) in values.iter().enumerate() {
+                for (bit_row, bit_values) in bit.iter().enumerate() {
+                    for (bit_column, bit_value) in bit_values.iter().enumerate() {
+                        next[row * 2 + bit_row][column * 2 + bit_column] = value * bit_value;
+                    }
+                }
+            }
+        }
+        matrix = next;
+    }
+    Ok(matrix)
+}
+
+/// Corrects a histogram of measured bitstrings by inverting the calibration matrix.
+/// Negative quasi-probabilities are clipped and the result is renormalized.
+pub fn mitigate_readout(
+    histogram: HashMap<String, u64>,
+    calibration_matrix: Vec<Vec<f64>>,
+) -> Result<HashMap<String, f64>, QsError> {
+    let invalid = |reason: &str| QsError::InvalidParameter {
+        parameter: "calibration_matrix".to_string(),
+        reason: reason.to_string(),
+    };
+
+    let size = calibration_matrix.len();
+    if !size.is_power_of_two() || calibration_matrix.iter().any(|row| row.len() != size) {
+        return Err(invalid("must be a square matrix with a power of two rows"));
+    }
+    let width = size.trailing_zeros() as usize;
+
+    let total: u64 = histogram.values().sum();
+    if total == 0 {
+        return Ok(HashMap::new());
+    }
+    let mut measured = vec![0.0; size];
+    for (bits, count) in &histogram {
+        let index = (bits.len() == width)
+            .then(|| usize::from_str_radix(bits, 2).ok())
+            .flatten()
+            .ok_or_else(|| QsError::InvalidParameter {
+                parameter: "histogram".to_string(),
+                reason: format!("`{bits}` is not a bitstring of {width} bits"),
+            })?;
+        measured[index] = *count as f64 / total as f64;
+    }
+
+    let prepared = solve(calibration_matrix, measured).ok_or_else(|| invalid("must be invertible"))?;
+    let clipped: Vec<f64> = prepared.iter().map(|p| p.max(0.0)).collect();
+    let norm: f64 = clipped.iter().sum();
+
+    Ok(clipped
+        .iter()
+        .enumerate()
+        .filter(|&(_, p)| *p > 0.0)
+        .map(|(index, p)| (format!("{index:0width$b}"), p / norm))
+        .collect())
+}
+
+// Gaussian elimination with partial pivoting.
+fn solve(mut matrix: Vec<Vec<f64>>, mut rhs: Vec<f64>) -> Option<Vec<f64>> {
+    let size = rhs.len();
+    for column in 0..size {
+        let pivot = (column..size).max_by(|&a, &b| matrix[a][column].abs().total_cmp(&matrix[b][column].abs()))?;
+        if matrix[pivot][column].abs() < 1e-12 {
+            return None;
+        }
+        matrix.swap(column, pivot);
+        rhs.swap(column, pivot);
+
+        let (upper, lower) = matrix.split_at_mut(column + 1);
+        let pivot_row = &upper[column];
+        let pivot_rhs = rhs[column];
+        for (row, row_rhs) in lower.iter_mut().zip(&mut rhs[column + 1..]) {
+            let factor = row[column] / pivot_row[column];
+            for (value, pivot_value) in row[column..].iter_mut().zip(&pivot_row[column..]) {
+                *value -= factor * pivot_value;
+            }
+            *row_rhs -= factor * pivot_rhs;
+        }
+    }
+
+    let mut solution = vec![0.0; size];
+    for row in (0..size).rev() {
+        let sum: f64 = (row + 1..size).map(|k| matrix[row][k] * solution[k]).sum();
+        solution[row] = (rhs[row] - sum) / matrix[row][row];
+    }
+    Some(solution)
+}
@@ -0,0 +1,267 @@
+use crate::estimation_comparison::configuration_name;
+use crate::estimation_result::{EstimationResult, TFactory};
+use crate::sim::QsError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+    PlainText,
+}
+
+struct Section {
+    title: String,
+    // label and one value per result
+    rows: Vec<(String, Vec<String>)>,
+}
+
+/// Renders the estimation results as a report. Several results are rendered side by side,
+/// one column per configuration.
+pub fn estimate_report(results: Vec<EstimationResult>, format: ReportFormat) -> Result<String, QsError> {
+    let Some(first) = results.first() else {
+        return Err(QsError::InvalidParameter {
+            parameter: "results".to_string(),
+            reason: "at least one estimation result is required".to_string(),
+        });
+    };
+
+    let names: Vec<String> = results.iter().map(configuration_name).collect();
+    let sections = if first.report_groups.is_empty() {
+        typed_sections(&results)
+    } else {
+        report_group_sections(&results)
+    };
+    let assumptions = &first.assumptions;
+
+    Ok(match format {
+        ReportFormat::Markdown => render_markdown(&names, &sections, assumptions),
+        ReportFormat::Html => render_html(&names, &sections, assumptions),
+        ReportFormat::PlainText => render_plain_text(&names, &sections, assumptions),
+    })
+}
+
+// The groups of the first result decide the layout, the other results are matched by group title and label.
+fn report_group_sections(results: &[EstimationResult]) -> Vec<Section> {
+    results[0]
+        .report_groups
+        .iter()
+        .map(|group| Section {
+            title: group.title.clone(),
+            rows: group
+                .entries
+                .iter()
+                .map(|entry| {
+                    let values = results
+                        .iter()
+                        .map(|result| {
+                            result
+                                .report_groups
+                                .iter()
+                                .find(|g| g.title == group.title)
+                                .and_then(|g| g.entries.iter().find(|e| e.label == entry.label))
+                                .map_or_else(String::new, |e| e.value.clone())
+                        })
+                        .collect();
+                    (entry.label.clone(), values)
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+// Used for results without report data, e.g. ones constructed by hand.
+fn typed_sections(results: &[EstimationResult]) -> Vec<Section> {
+    let row = |label: &str, value: &dyn Fn(&EstimationResult) -> String| {
+        (label.to_string(), results.iter().map(value).collect::<Vec<_>>())
+    };
+
+    let mut sections = vec![
+        Section {
+            title: "Physical resource estimates".to_string(),
+            rows: vec![
+                row("Runtime", &|r| format!("{} ns", r.physical_counts.runtime_ns)),
+                row("rQOPS", &|r| r.physical_counts.rqops.to_string()),
+                row("Physical qubits", &|r| r.physical_counts.physical_qubits.to_string()),
+            ],
+        },
+        Section {
+            title: "Resource estimates breakdown".to_string(),
+            rows: vec![
+                row("Logical algorithmic qubits", &|r| r.physical_counts.breakdown.algorithmic_logical_qubits.to_string()),
+                row("Algorithmic depth", &|r| r.physical_counts.breakdown.algorithmic_logical_depth.to_string()),
+                row("Logical depth", &|r| r.physical_counts.breakdown.logical_depth.to_string()),
+                row("Number of T states", &|r| r.physical_counts.breakdown.num_t_states.to_string()),
+                row("Number of T factories", &|r| r.physical_counts.breakdown.num_t_factories.to_string()),
+                row("Physical algorithmic qubits", &|r| r.physical_counts.breakdown.physical_qubits_for_algorithm.to_string()),
+                row("Physical T factory qubits", &|r| r.physical_counts.breakdown.physical_qubits_for_t_factories.to_string()),
+            ],
+        },
+        Section {
+            title: "Logical qubit parameters".to_string(),
+            rows: vec![
+                row("QEC scheme", &|r| r.qec_scheme.clone()),
+                row("Code distance", &|r| r.logical_qubit.code_distance.to_string()),
+                row("Physical qubits", &|r| r.logical_qubit.physical_qubits.to_string()),
+                row("Logical cycle time", &|r| format!("{} ns", r.logical_qubit.logical_cycle_time_ns)),
+                row("Logical qubit error rate", &|r| rate(r.logical_qubit.logical_error_rate)),
+            ],
+        },
+    ];
+
+    if results.iter().any(|r| r.t_factory.is_some()) {
+        fn t_factory(value: impl Fn(&TFactory) -> String) -> impl Fn(&EstimationResult) -> String {
+            move |r| r.t_factory.as_ref().map_or_else(String::new, &value)
+        }
+        sections.push(Section {
+            title: "T factory parameters".to_string(),
+            rows: vec![
+                row("Physical qubits", &t_factory(|t| t.physical_qubits.to_string())),
+                row("Runtime", &t_factory(|t| format!("{} ns", t.runtime_ns))),
+                row("Number of output T states per run", &t_factory(|t| t.num_t_states.to_string())),
+                row("Number of input T states per run", &t_factory(|t| t.num_input_t_states.to_string())),
+                row("Distillation rounds", &t_factory(|t| t.num_rounds.to_string())),
+                row("Distillation units", &t_factory(|t| t.unit_name_per_round.join(", "))),
+                row("Logical T state error rate", &t_factory(|t| rate(t.logical_error_rate))),
+            ],
+        });
+    }
+
+    sections.push(Section {
+        title: "Assumed error budget".to_string(),
+        rows: vec![
+            row("Logical error probability", &|r| rate(r.error_budget.logical)),
+            row("T distillation error probability", &|r| rate(r.error_budget.t_states)),
+            row("Rotation synthesis error probability", &|r| rate(r.error_budget.rotations)),
+        ],
+    });
+    sections
+}
+
+fn render_markdown(names: &[String], sections: &[Section], assumptions: &[String]) -> String {
+    let cell = |text: &str| text.replace('|', "\\|");
+    let mut out = String::from("# Resource estimates\n");
+
+    for section in sections {
+        out.push_str(&format!("\n## {}\n\n", section.title));
+        out.push_str("| Metric |");
+        for name in names {
+            out.push_str(&format!(" {} |", cell(name)));
+        }
+        out.push_str("\n|---|");
+        out.push_str(&"---|".repeat(names.len()));
+        out.push('\n');
+        for (label, values) in &section.rows {
+            out.push_str(&format!("| {} |", cell(label)));
+            for value in values {
+                out.push_str(&format!(" {} |", cell(value)));
+            }
+            out.push('\n');
+        }
+    }
+
+    if !assumptions.is_empty() {
+        out.push_str("\n## Assumptions\n\n");
+        for assumption in assumptions {
+            out.push_str(&format!("- {}\n", assumption));
+        }
+    }
+    out
+}
+
+fn render_html(names: &[String], sections: &[Section], assumptions: &[String]) -> String {
+    let mut out = String::from("<h1>Resource estimates</h1>\n");
+
+    for section in sections {
+        out.push_str(&format!("<h2>{}</h2>\n<table>\n<thead>\n<tr><th>Metric</th>", escape_html(&section.title)));
+        for name in names {
+            out.push_str(&format!("<th>{}</th>", escape_html(name)));
+        }
+        out.push_str("</tr>\n</thead>\n<tbody>\n");
+        for (label, values) in &section.rows {
+            out.push_str(&format!("<tr><td>{}</td>", escape_html(label)));
+            for value in values {
+                out.push_str(&format!("<td>{}</td>", escape_html(value)));
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</tbody>\n</table>\n");
+    }
+
+    if !assumptions.is_empty() {
+        out.push_str("<h2>Assumptions</h2>\n<ul>\n");
+        for assumption in assumptions {
+            out.push_str(&format!("<li>{}</li>\n", escape_html(assumption)));
+        }
+        out.push_str("</ul>\n");
+    }
+    out
+}
+
+fn render_plain_text(names: &[String], sections: &[Section], assumptions: &[String]) -> String {
+    let title = "Resource estimates";
+    let mut out = format!("{}\n{}\n", title, "=".repeat(title.len()));
+
+    for section in sections {
+        out.push_str(&format!("\n{}\n{}\n", section.title, "-".repeat(section.title.chars().count())));
+
+        let label_width = section
+            .rows
+            .iter()
+            .map(|(label, _)| label.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max("Metric".len());
+        let value_widths: Vec<usize> = names
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| {
+                section
+                    .rows
+                    .iter()
+                    .map(|(_, values)| values[idx].chars().count())
+                    .max()
+                    .unwrap_or(0)
+                    .max(name.chars().count())
+            })
+            .collect();
+
+        let line = |label: &str, values: &[&str]| {
+            let mut line = pad(label, label_width);
+            for (value, width) in values.iter().zip(&value_widths) {
+                line.push_str("  ");
+                line.push_str(&pad(value, *width));
+            }
+            format!("{}\n", line.trim_end())
+        };
+
+        let header: Vec<&str> = names.iter().map(String::as_str).collect();
+        out.push_str(&line("Metric", &header));
+        for (label, values) in &section.rows {
+            let values: Vec<&str> = values.iter().map(String::as_str).collect();
+            out.push_str(&line(label, &values));
+        }
+    }
+
+    if !assumptions.is_empty() {
+        out.push_str("\nAssumptions\n-----------\n");
+        for assumption in assumptions {
+            out.push_str(&format!("* {}\n", assumption));
+        }
+    }
+    out
+}
+
+fn rate(value: f64) -> String {
+    if value == 0.0 { "0".to_string() } else { format!("{value:e}") }
+}
+
+fn pad(text: &str, width: usize) -> String {
+    format!("{}{}", text, " ".repeat(width.saturating_sub(text.chars().count())))
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
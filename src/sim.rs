@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use num_bigint::BigUint;
@@ -5,19 +6,53 @@ use num_complex::Complex64;
 use qsc::interpret::output;
 use qsc::interpret::output::Receiver;
 use qsc::interpret::{self, Interpreter};
+use qsc::hir::PackageId;
+use qsc::qasm::io::InMemorySourceResolver;
+use qsc::qasm::{CompilerConfig, OutputSemantics, ProgramType, QubitSemantics};
 use qsc::{
-    LanguageFeatures, PackageType, PauliNoise, SourceMap, SparseSim, TargetCapabilityFlags,
+    Backend, LanguageFeatures, PackageType, PauliNoise, SourceMap, SparseSim, TargetCapabilityFlags,
     format_state_id,
 };
-use resource_estimator::{estimate_entry, estimate_expr};
+use qsc_eval::val::{self, Value};
+use resource_estimator::{estimate_entry, estimate_expr, estimate_physical_resources_from_json};
 use thiserror::Error;
 
-use crate::noise::{Noise, PauliNoiseDistribution};
+use crate::estimation_result::LogicalCounts;
+use crate::noise::{DeviceNoiseModel, IdleNoise, Noise, NoiseModel, PauliNoiseDistribution};
+use crate::density_matrix::DensityMatrixSim;
+use crate::noisy_backend::NoisyBackend;
+use crate::readout::ReadoutError;
+use crate::stabilizer::StabilizerSim;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Simulator {
+    #[default]
+    Sparse,
+    /// Supports every `Noise`, including non-Pauli channels, for up to 10 qubits.
+    DensityMatrix,
+    /// Supports only Clifford gates and Pauli noise, for hundreds of qubits.
+    Stabilizer,
+}
 
+#[derive(Clone)]
 pub struct ExecutionOptions {
     pub shots: u32,
     pub noise: Noise,
     pub qubit_loss: Option<f64>,
+    /// Per-gate noise. Gates without their own channel fall back to `noise`.
+    pub noise_model: Option<NoiseModel>,
+    /// Readout error of every qubit without its own entry in `qubit_readout_errors`.
+    pub readout_error: Option<ReadoutError>,
+    /// Readout errors keyed by qubit allocation index.
+    pub qubit_readout_errors: HashMap<u64, ReadoutError>,
+    /// Replaces `noise` on the listed qubits, keyed by qubit allocation index. Gates with their own
+    /// channel in `noise_model` keep it.
+    pub qubit_noise: HashMap<u64, Noise>,
+    /// Replaces `qubit_loss` on the listed qubits, keyed by qubit allocation index.
+    pub qubit_losses: HashMap<u64, f64>,
+    /// Noise on the qubits that are not acted on in a layer of the schedule.
+    pub idle_noise: Option<IdleNoise>,
+    pub simulator: Simulator,
 }
 
 impl ExecutionOptions {
@@ -26,6 +61,7 @@ impl ExecutionOptions {
             shots,
             noise,
             qubit_loss,
+            ..Default::default()
         }
     }
 
@@ -49,6 +85,81 @@ impl ExecutionOptions {
             ..Default::default()
         }
     }
+
+    pub fn with_noise_model(&self, noise_model: NoiseModel) -> Arc<Self> {
+        Arc::new(Self {
+            noise_model: Some(noise_model),
+            ..self.clone()
+        })
+    }
+
+    pub fn with_readout_error(&self, readout_error: ReadoutError) -> Arc<Self> {
+        Arc::new(Self {
+            readout_error: Some(readout_error),
+            ..self.clone()
+        })
+    }
+
+    pub fn with_qubit_readout_error(&self, qubit: u64, readout_error: ReadoutError) -> Arc<Self> {
+        let mut options = self.clone();
+        options.qubit_readout_errors.insert(qubit, readout_error);
+        Arc::new(options)
+    }
+
+    pub fn with_simulator(&self, simulator: Simulator) -> Arc<Self> {
+        Arc::new(Self {
+            simulator,
+            ..self.clone()
+        })
+    }
+
+    pub fn with_qubit_noise(&self, qubit: u64, noise: Noise) -> Arc<Self> {
+        let mut options = self.clone();
+        options.qubit_noise.insert(qubit, noise);
+        Arc::new(options)
+    }
+
+    pub fn with_qubit_loss(&self, qubit: u64, qubit_loss: f64) -> Arc<Self> {
+        let mut options = self.clone();
+        options.qubit_losses.insert(qubit, qubit_loss);
+        Arc::new(options)
+    }
+
+    pub fn with_idle_noise(&self, idle_noise: IdleNoise) -> Arc<Self> {
+        Arc::new(Self {
+            idle_noise: Some(idle_noise),
+            ..self.clone()
+        })
+    }
+
+    /// Applies every setting of a device noise model, e.g. one loaded with `noise_model_from_json`.
+    pub fn with_device_noise_model(&self, model: DeviceNoiseModel) -> Arc<Self> {
+        Arc::new(Self {
+            noise: model.noise,
+            noise_model: model.gates,
+            readout_error: model.readout_error,
+            qubit_readout_errors: model.qubit_readout_errors.unwrap_or_default(),
+            qubit_loss: model.qubit_loss,
+            qubit_noise: model.qubit_noise.unwrap_or_default(),
+            qubit_losses: model.qubit_losses.unwrap_or_default(),
+            idle_noise: model.idle_noise,
+            ..self.clone()
+        })
+    }
+
+    /// Whether the gate noise differs between gates or qubits, which `SparseSim` cannot model itself.
+    fn has_gate_specific_noise(&self) -> bool {
+        self.noise_model.is_some() || !self.qubit_noise.is_empty()
+    }
+
+    fn needs_noisy_backend(&self) -> bool {
+        self.has_gate_specific_noise()
+            || self.qubit_loss.is_some()
+            || !self.qubit_losses.is_empty()
+            || self.idle_noise.is_some()
+            || self.readout_error.is_some()
+            || !self.qubit_readout_errors.is_empty()
+    }
 }
 
 impl Default for ExecutionOptions {
@@ -59,6 +170,13 @@ impl Default for ExecutionOptions {
                 noise: PauliNoiseDistribution::new(0.0, 0.0, 0.0).unwrap(),
             },
             qubit_loss: None,
+            noise_model: None,
+            readout_error: None,
+            qubit_readout_errors: HashMap::new(),
+            qubit_noise: HashMap::new(),
+            qubit_losses: HashMap::new(),
+            idle_noise: None,
+            simulator: Simulator::Sparse,
         }
     }
 }
@@ -68,7 +186,7 @@ pub fn run_qs(source: &str) -> Result<ExecutionState, QsError> {
         create_interpreter(Some(source), PackageType::Exe, TargetCapabilityFlags::all())?;
     let mut rec = ExecutionState::default();
     let result = interpreter.eval_entry(&mut rec)?;
-    rec.set_result(result.to_string());
+    rec.set_result(&result);
     return Ok(rec);
 }
 
@@ -76,9 +194,53 @@ pub fn run_qs_with_options(
     source: &str,
     options: Arc<ExecutionOptions>,
 ) -> Result<Vec<ExecutionState>, QsError> {
-    let mut results: Vec<ExecutionState> = Vec::new();
     let mut interpreter =
         create_interpreter(Some(source), PackageType::Exe, TargetCapabilityFlags::all())?;
+    run_compiled(&mut interpreter, &options, None)
+}
+
+/// Runs the shots of an already compiled program, with seeded simulators if `seed` is set.
+pub(crate) fn run_compiled(
+    interpreter: &mut Interpreter,
+    options: &ExecutionOptions,
+    seed: Option<u64>,
+) -> Result<Vec<ExecutionState>, QsError> {
+    if options.simulator == Simulator::DensityMatrix {
+        if options.qubit_loss.is_some() || !options.qubit_losses.is_empty() {
+            return Err(QsError::InvalidParameter {
+                parameter: "qubit_loss".to_string(),
+                reason: "qubit loss is not supported by the density matrix simulator".to_string(),
+            });
+        }
+        if !options.qubit_noise.is_empty() {
+            return Err(QsError::InvalidParameter {
+                parameter: "qubit_noise".to_string(),
+                reason: "per-qubit noise is not supported by the density matrix simulator".to_string(),
+            });
+        }
+        if options.idle_noise.is_some() {
+            return Err(QsError::InvalidParameter {
+                parameter: "idle_noise".to_string(),
+                reason: "idle noise is not supported by the density matrix simulator".to_string(),
+            });
+        }
+        // the density matrix simulator applies the gate noise itself, the wrapper only the readout errors
+        let mut sim = NoisyBackend::new(DensityMatrixSim::new(options)?, options, false)?;
+        return run_shots(interpreter, &mut sim, options.shots, seed);
+    }
+
+    if options.simulator == Simulator::Stabilizer {
+        // the tableau is noiseless, all noise is applied by the wrapper as Pauli gates
+        let mut sim = NoisyBackend::new(StabilizerSim::new(), options, true)?;
+        return run_shots(interpreter, &mut sim, options.shots, seed);
+    }
+
+    // the wrapper applies all noise around a noiseless simulator, otherwise the Pauli errors it
+    // injects as gates would pick up the noise of the simulator as well
+    if options.needs_noisy_backend() {
+        let mut sim = NoisyBackend::new(SparseSim::new(), options, true)?;
+        return run_shots(interpreter, &mut sim, options.shots, seed);
+    }
 
     let noise_probabilities = options.noise.to_distribution()?;
     let mut sim = if noise_probabilities.x == 0.0
@@ -95,16 +257,42 @@ pub fn run_qs_with_options(
         .map_err(|error| QsError::ErrorMessage { error_text: error })?;
         SparseSim::new_with_noise(&noise)
     };
+    run_shots(interpreter, &mut sim, options.shots, seed)
+}
+
+/// A simulator that can fail a shot, e.g. when the program does not fit the simulator.
+pub(crate) trait SimulatorBackend: Backend {
+    fn take_error(&mut self) -> Option<String> {
+        None
+    }
 
-    if let Some(qubit_loss) = options.qubit_loss {
-        sim.set_loss(qubit_loss);
+    /// The qubits lost since the last call, in ascending order.
+    fn take_lost_qubits(&mut self) -> Vec<usize> {
+        Vec::new()
     }
+}
 
-    let shots = options.shots;
+impl SimulatorBackend for SparseSim {}
+
+fn run_shots(
+    interpreter: &mut Interpreter,
+    sim: &mut impl SimulatorBackend,
+    shots: u32,
+    seed: Option<u64>,
+) -> Result<Vec<ExecutionState>, QsError> {
+    if seed.is_some() {
+        interpreter.set_classical_seed(seed);
+        sim.set_seed(seed);
+    }
+    let mut results: Vec<ExecutionState> = Vec::new();
     for _ in 0..shots {
         let mut rec = ExecutionState::default();
-        let result = interpreter.eval_entry_with_sim(&mut sim, &mut rec)?;
-        rec.set_result(result.to_string());
+        let result = interpreter.eval_entry_with_sim(sim, &mut rec)?;
+        if let Some(error_text) = sim.take_error() {
+            return Err(QsError::ErrorMessage { error_text });
+        }
+        rec.lost_qubits = sim.take_lost_qubits().into_iter().map(|q| q as u64).collect();
+        rec.set_result(&result);
         results.push(rec)
     }
 
@@ -140,6 +328,25 @@ pub fn estimate_expression(
     return Ok(result);
 }
 
+pub fn estimate_logical_counts(
+    counts: LogicalCounts,
+    job_params: Option<String>,
+) -> Result<String, QsError> {
+    let logical_resources = serde_json::json!({
+        "numQubits": counts.num_qubits,
+        "tCount": counts.t_count,
+        "rotationCount": counts.rotation_count,
+        "rotationDepth": counts.rotation_depth,
+        "cczCount": counts.ccz_count,
+        "ccixCount": counts.ccix_count,
+        "measurementCount": counts.measurement_count,
+    });
+    let params = job_params.as_deref().unwrap_or("[{}]");
+    let result = estimate_physical_resources_from_json(&logical_resources.to_string(), params)
+        .map_err(|error| QsError::from(vec![error]))?;
+    return Ok(result);
+}
+
 pub(crate) fn create_interpreter(
     source: Option<&str>,
     package_type: PackageType,
@@ -168,6 +375,40 @@ pub(crate) fn create_interpreter(
     return Ok(interpreter);
 }
 
+// Compiles an OpenQASM program with the OpenQASM front end of the QDK into an interpreter
+// whose entry point is the whole program.
+pub(crate) fn create_qasm_interpreter(source: &str) -> Result<Interpreter, QsError> {
+    let config = CompilerConfig::new(
+        QubitSemantics::Qiskit,
+        OutputSemantics::ResourceEstimation,
+        ProgramType::File,
+        Some("program".into()),
+        None,
+    );
+    let mut resolver = InMemorySourceResolver::from_iter([]);
+    let unit = qsc::qasm::parse_and_compile_to_qsharp_ast_with_config(source, "temp.qasm", Some(&mut resolver), config);
+    let (source_map, errors, package, _) = unit.into_tuple();
+    if !errors.is_empty() {
+        let error_text = errors.iter().map(|error| format!("{error:?}")).collect::<Vec<_>>().join(", ");
+        return Err(QsError::ErrorMessage { error_text });
+    }
+
+    let capabilities = TargetCapabilityFlags::all();
+    let (std_id, qasm_id, mut store) = qsc::qasm::package_store_with_qasm(capabilities);
+    let dependencies = [
+        (PackageId::CORE, None),
+        (std_id, None),
+        (qasm_id, Some("QasmStd".into())),
+    ];
+    let (unit, errors) = qsc::compile::compile_ast(&store, &dependencies, package, source_map, PackageType::Exe, capabilities);
+    if !errors.is_empty() {
+        return Err(errors.into_iter().map(interpret::Error::Compile).collect::<Vec<_>>().into());
+    }
+    let package_id = store.insert(unit);
+
+    Ok(Interpreter::from(false, store, package_id, capabilities, LanguageFeatures::default(), &dependencies)?)
+}
+
 pub struct QubitState {
     pub id: String,
     pub amplitude_real: f64,
@@ -179,11 +420,16 @@ pub struct ExecutionState {
     pub qubit_count: u64,
     pub messages: Vec<String>,
     pub result: Option<String>,
+    /// The qubits lost during the shot, by allocation index.
+    pub lost_qubits: Vec<u64>,
+    /// The measurement results in the returned value, in order of appearance.
+    pub outcomes: Vec<MeasurementOutcome>,
 }
 
 impl ExecutionState {
-    fn set_result(&mut self, result: String) {
-        self.result = Some(result);
+    fn set_result(&mut self, result: &Value) {
+        self.result = Some(result.to_string());
+        collect_outcomes(result, &mut self.outcomes);
     }
 }
 
@@ -194,10 +440,31 @@ impl Default for ExecutionState {
             qubit_count: 0,
             messages: Vec::new(),
             result: None,
+            lost_qubits: Vec::new(),
+            outcomes: Vec::new(),
         }
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MeasurementOutcome {
+    Zero,
+    One,
+    /// The measured qubit was lost.
+    Loss,
+}
+
+fn collect_outcomes(value: &Value, outcomes: &mut Vec<MeasurementOutcome>) {
+    match value {
+        Value::Result(val::Result::Val(false)) => outcomes.push(MeasurementOutcome::Zero),
+        Value::Result(val::Result::Val(true)) => outcomes.push(MeasurementOutcome::One),
+        Value::Result(val::Result::Loss) => outcomes.push(MeasurementOutcome::Loss),
+        Value::Tuple(values) => values.iter().for_each(|value| collect_outcomes(value, outcomes)),
+        Value::Array(values) => values.iter().for_each(|value| collect_outcomes(value, outcomes)),
+        _ => {}
+    }
+}
+
 impl Receiver for ExecutionState {
     fn state(
         &mut self,
@@ -232,6 +499,8 @@ impl Receiver for ExecutionState {
 pub enum QsError {
     #[error("Error with message: `{error_text}`")]
     ErrorMessage { error_text: String },
+    #[error("Invalid parameter `{parameter}`: {reason}")]
+    InvalidParameter { parameter: String, reason: String },
 }
 
 impl From<Vec<interpret::Error>> for QsError {
@@ -260,8 +529,10 @@ impl From<Vec<resource_estimator::Error>> for QsError {
             match error {
                 resource_estimator::Error::Interpreter(interpret_error) => {
                     let qs_error: QsError = vec![interpret_error].into();
-                    let QsError::ErrorMessage { error_text } = qs_error;
-                    error_message.push_str(&error_text);
+                    match qs_error {
+                        QsError::ErrorMessage { error_text } => error_message.push_str(&error_text),
+                        other => error_message.push_str(&other.to_string()),
+                    }
                 }
                 resource_estimator::Error::Estimation(estimates_error) => {
                     // Handle `estimates::Error` similarly, if applicable
@@ -0,0 +1,384 @@
+use std::f64::consts::FRAC_PI_2;
+
+use num_bigint::BigUint;
+use num_complex::Complex64;
+use qsc::Backend;
+use qsc_eval::val;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::sim::SimulatorBackend;
+
+/// A Pauli product with a sign, one row of the tableau.
+#[derive(Clone)]
+struct Row {
+    x: Vec<bool>,
+    z: Vec<bool>,
+    /// Whether the sign is negative.
+    r: bool,
+}
+
+impl Row {
+    fn identity(n: usize) -> Self {
+        Self {
+            x: vec![false; n],
+            z: vec![false; n],
+            r: false,
+        }
+    }
+
+    /// Multiplies `other` into this row, keeping track of the sign.
+    fn multiply(&mut self, other: &Row) {
+        // the exponent of i picked up by each single-qubit product
+        let g = |x1: bool, z1: bool, x2: bool, z2: bool| -> i32 {
+            match (x1, z1) {
+                (false, false) => 0,
+                (true, true) => z2 as i32 - x2 as i32,
+                (true, false) => z2 as i32 * (2 * x2 as i32 - 1),
+                (false, true) => x2 as i32 * (1 - 2 * z2 as i32),
+            }
+        };
+        let mut sum = 2 * self.r as i32 + 2 * other.r as i32;
+        for j in 0..self.x.len() {
+            sum += g(other.x[j], other.z[j], self.x[j], self.z[j]);
+            self.x[j] ^= other.x[j];
+            self.z[j] ^= other.z[j];
+        }
+        self.r = sum.rem_euclid(4) == 2;
+    }
+}
+
+/// Simulation of Clifford circuits with the stabilizer tableau of Aaronson and Gottesman (CHP).
+/// Memory and time grow with n^2, so that codes with hundreds of qubits can be simulated.
+/// Non-Clifford gates fail the shot.
+pub(crate) struct StabilizerSim {
+    /// Rows `0..n` are the destabilizers, rows `n..2n` the stabilizers; the qubit id is the column.
+    rows: Vec<Row>,
+    allocated: Vec<bool>,
+    rng: StdRng,
+    error: Option<String>,
+}
+
+impl StabilizerSim {
+    pub(crate) fn new() -> Self {
+        Self {
+            rows: Vec::new(),
+            allocated: Vec::new(),
+            rng: StdRng::from_entropy(),
+            error: None,
+        }
+    }
+
+    fn qubits(&self) -> usize {
+        self.allocated.len()
+    }
+
+    fn not_clifford(&mut self, gate: &str) {
+        if self.error.is_none() {
+            self.error = Some(format!(
+                "The stabilizer simulator only supports Clifford gates, `{gate}` is not a Clifford gate"
+            ));
+        }
+    }
+
+    /// The number of quarter turns of a rotation, if it is a Clifford gate.
+    fn quarter_turns(&mut self, gate: &str, theta: f64) -> Option<u8> {
+        let turns = theta / FRAC_PI_2;
+        if (turns - turns.round()).abs() > 1e-9 {
+            self.not_clifford(&format!("{gate}({theta})"));
+            return None;
+        }
+        Some(turns.round().rem_euclid(4.0) as u8)
+    }
+
+    fn apply_h(&mut self, q: usize) {
+        for row in &mut self.rows {
+            row.r ^= row.x[q] && row.z[q];
+            std::mem::swap(&mut row.x[q], &mut row.z[q]);
+        }
+    }
+
+    fn apply_s(&mut self, q: usize) {
+        for row in &mut self.rows {
+            row.r ^= row.x[q] && row.z[q];
+            row.z[q] ^= row.x[q];
+        }
+    }
+
+    fn apply_sadj(&mut self, q: usize) {
+        for row in &mut self.rows {
+            row.r ^= row.x[q] && !row.z[q];
+            row.z[q] ^= row.x[q];
+        }
+    }
+
+    fn apply_cx(&mut self, ctl: usize, q: usize) {
+        for row in &mut self.rows {
+            row.r ^= row.x[ctl] && row.z[q] && (row.x[q] == row.z[ctl]);
+            row.x[q] ^= row.x[ctl];
+            row.z[ctl] ^= row.z[q];
+        }
+    }
+
+    fn apply_cz(&mut self, ctl: usize, q: usize) {
+        self.apply_h(q);
+        self.apply_cx(ctl, q);
+        self.apply_h(q);
+    }
+
+    /// `Rz` by a number of quarter turns, which equals `S` to that power up to a global phase.
+    fn apply_rz(&mut self, turns: u8, q: usize) {
+        for _ in 0..turns {
+            self.apply_s(q);
+        }
+    }
+
+    fn apply_rzz(&mut self, turns: u8, q0: usize, q1: usize) {
+        self.apply_cx(q0, q1);
+        self.apply_rz(turns, q1);
+        self.apply_cx(q0, q1);
+    }
+
+    fn apply_rxx(&mut self, turns: u8, q0: usize, q1: usize) {
+        self.apply_h(q0);
+        self.apply_h(q1);
+        self.apply_rzz(turns, q0, q1);
+        self.apply_h(q0);
+        self.apply_h(q1);
+    }
+
+    /// Measures in the Z basis, collapsing the state.
+    fn measure(&mut self, q: usize) -> bool {
+        let n = self.qubits();
+        let Some(p) = (n..2 * n).find(|&i| self.rows[i].x[q]) else {
+            // the outcome is determined, it is the sign of the product of the stabilizers
+            // whose destabilizers anticommute with Z
+            let mut scratch = Row::identity(n);
+            for i in 0..n {
+                if self.rows[i].x[q] {
+                    scratch.multiply(&self.rows[i + n]);
+                }
+            }
+            return scratch.r;
+        };
+
+        let pivot = self.rows[p].clone();
+        for i in (0..2 * n).filter(|&i| i != p) {
+            if self.rows[i].x[q] {
+                self.rows[i].multiply(&pivot);
+            }
+        }
+        let outcome = self.rng.r#gen::<bool>();
+        let mut stabilizer = Row::identity(n);
+        stabilizer.z[q] = true;
+        stabilizer.r = outcome;
+        self.rows[p - n] = pivot;
+        self.rows[p] = stabilizer;
+        outcome
+    }
+
+    fn reset_qubit(&mut self, q: usize) {
+        if self.measure(q) {
+            self.x(q);
+        }
+    }
+
+    fn is_deterministic_zero(&self, q: usize) -> bool {
+        let n = self.qubits();
+        if (n..2 * n).any(|i| self.rows[i].x[q]) {
+            return false;
+        }
+        let mut scratch = Row::identity(n);
+        for i in (0..n).filter(|&i| self.rows[i].x[q]) {
+            scratch.multiply(&self.rows[i + n]);
+        }
+        !scratch.r
+    }
+
+    /// Adds a qubit in the zero state, with destabilizer X and stabilizer Z.
+    fn grow(&mut self) -> usize {
+        let q = self.qubits();
+        for row in &mut self.rows {
+            row.x.push(false);
+            row.z.push(false);
+        }
+        let mut destabilizer = Row::identity(q + 1);
+        destabilizer.x[q] = true;
+        let mut stabilizer = Row::identity(q + 1);
+        stabilizer.z[q] = true;
+        self.rows.insert(q, destabilizer);
+        self.rows.push(stabilizer);
+        self.allocated.push(true);
+        q
+    }
+}
+
+impl SimulatorBackend for StabilizerSim {
+    fn take_error(&mut self) -> Option<String> {
+        self.error.take()
+    }
+}
+
+impl Backend for StabilizerSim {
+    fn ccx(&mut self, _ctl0: usize, _ctl1: usize, _q: usize) {
+        self.not_clifford("ccx");
+    }
+
+    fn cx(&mut self, ctl: usize, q: usize) {
+        self.apply_cx(ctl, q);
+    }
+
+    fn cy(&mut self, ctl: usize, q: usize) {
+        // S X S^dagger = Y
+        self.apply_sadj(q);
+        self.apply_cx(ctl, q);
+        self.apply_s(q);
+    }
+
+    fn cz(&mut self, ctl: usize, q: usize) {
+        self.apply_cz(ctl, q);
+    }
+
+    fn h(&mut self, q: usize) {
+        self.apply_h(q);
+    }
+
+    fn m(&mut self, q: usize) -> val::Result {
+        val::Result::Val(self.measure(q))
+    }
+
+    fn mresetz(&mut self, q: usize) -> val::Result {
+        let outcome = self.measure(q);
+        if outcome {
+            self.x(q);
+        }
+        val::Result::Val(outcome)
+    }
+
+    fn reset(&mut self, q: usize) {
+        self.reset_qubit(q);
+    }
+
+    fn rx(&mut self, theta: f64, q: usize) {
+        if let Some(turns) = self.quarter_turns("rx", theta) {
+            self.apply_h(q);
+            self.apply_rz(turns, q);
+            self.apply_h(q);
+        }
+    }
+
+    fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
+        if let Some(turns) = self.quarter_turns("rxx", theta) {
+            self.apply_rxx(turns, q0, q1);
+        }
+    }
+
+    fn ry(&mut self, theta: f64, q: usize) {
+        if let Some(turns) = self.quarter_turns("ry", theta) {
+            self.apply_sadj(q);
+            self.apply_h(q);
+            self.apply_rz(turns, q);
+            self.apply_h(q);
+            self.apply_s(q);
+        }
+    }
+
+    fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
+        if let Some(turns) = self.quarter_turns("ryy", theta) {
+            self.apply_sadj(q0);
+            self.apply_sadj(q1);
+            self.apply_rxx(turns, q0, q1);
+            self.apply_s(q0);
+            self.apply_s(q1);
+        }
+    }
+
+    fn rz(&mut self, theta: f64, q: usize) {
+        if let Some(turns) = self.quarter_turns("rz", theta) {
+            self.apply_rz(turns, q);
+        }
+    }
+
+    fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
+        if let Some(turns) = self.quarter_turns("rzz", theta) {
+            self.apply_rzz(turns, q0, q1);
+        }
+    }
+
+    fn sadj(&mut self, q: usize) {
+        self.apply_sadj(q);
+    }
+
+    fn s(&mut self, q: usize) {
+        self.apply_s(q);
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        for row in &mut self.rows {
+            row.x.swap(q0, q1);
+            row.z.swap(q0, q1);
+        }
+    }
+
+    fn tadj(&mut self, _q: usize) {
+        self.not_clifford("tadj");
+    }
+
+    fn t(&mut self, _q: usize) {
+        self.not_clifford("t");
+    }
+
+    fn x(&mut self, q: usize) {
+        for row in &mut self.rows {
+            row.r ^= row.z[q];
+        }
+    }
+
+    fn y(&mut self, q: usize) {
+        for row in &mut self.rows {
+            row.r ^= row.x[q] ^ row.z[q];
+        }
+    }
+
+    fn z(&mut self, q: usize) {
+        for row in &mut self.rows {
+            row.r ^= row.x[q];
+        }
+    }
+
+    fn qubit_allocate(&mut self) -> usize {
+        // released qubits are reset, so they can be reused as they are
+        match self.allocated.iter().position(|allocated| !allocated) {
+            Some(q) => {
+                self.allocated[q] = true;
+                q
+            }
+            None => self.grow(),
+        }
+    }
+
+    fn qubit_release(&mut self, q: usize) -> bool {
+        let is_zero = self.is_deterministic_zero(q);
+        self.reset_qubit(q);
+        self.allocated[q] = false;
+        is_zero
+    }
+
+    fn qubit_swap_id(&mut self, q0: usize, q1: usize) {
+        self.swap(q0, q1);
+        self.allocated.swap(q0, q1);
+    }
+
+    /// No amplitudes, the stabilizer state is not expanded into a state vector.
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex64)>, usize) {
+        (Vec::new(), self.allocated.iter().filter(|&&allocated| allocated).count())
+    }
+
+    fn qubit_is_zero(&mut self, q: usize) -> bool {
+        self.is_deterministic_zero(q)
+    }
+
+    fn set_seed(&mut self, seed: Option<u64>) {
+        self.rng = seed.map_or_else(StdRng::from_entropy, StdRng::seed_from_u64);
+    }
+}
@@ -0,0 +1,124 @@
+use qsc::interpret::CircuitEntryPoint;
+use qsc_circuit::{Circuit, ComponentGrid, Operation, Register};
+use std::collections::{BTreeSet, HashMap};
+
+use crate::circuit::{generate_circuit, CircuitGenerationOptions};
+use crate::sim::QsError;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CircuitStats {
+    /// Number of qubits used by the circuit.
+    pub width: u64,
+    pub depth: u64,
+    /// Depth of each qubit, in the order of the circuit qubits.
+    pub qubit_depths: Vec<u64>,
+    pub gate_count: u64,
+    /// Number of gates keyed by gate name, with controlled and adjoint variants counted under the same name.
+    pub gate_counts: HashMap<String, u64>,
+    pub two_qubit_gate_count: u64,
+    /// Number of `T` and adjoint `T` gates without controls. Gates whose decomposition contains
+    /// T gates, like `CCNOT` or controlled `T`, are not counted.
+    pub t_count: u64,
+    pub measurement_count: u64,
+}
+
+pub fn circuit_stats(source: &str, options: CircuitGenerationOptions) -> Result<CircuitStats, QsError> {
+    let circuit = generate_circuit(source, CircuitEntryPoint::EntryPoint, &options)?;
+    Ok(circuit_to_stats(&circuit))
+}
+
+pub fn circuit_stats_operation(operation: &str, source: &str, options: CircuitGenerationOptions) -> Result<CircuitStats, QsError> {
+    let circuit = generate_circuit(
+        source,
+        CircuitEntryPoint::Operation(operation.to_string()),
+        &options,
+    )?;
+    Ok(circuit_to_stats(&circuit))
+}
+
+pub fn circuit_to_stats(c: &Circuit) -> CircuitStats {
+    let mut stats = CircuitStats {
+        width: c.qubits.len() as u64,
+        ..Default::default()
+    };
+    let mut depths: HashMap<Wire, u64> = HashMap::new();
+
+    collect_stats(&c.component_grid, &mut stats, &mut depths);
+
+    stats.qubit_depths = c
+        .qubits
+        .iter()
+        .map(|q| depths.get(&(q.id, None)).copied().unwrap_or(0))
+        .collect();
+    stats.depth = stats.qubit_depths.iter().copied().max().unwrap_or(0);
+    stats
+}
+
+// A qubit, or one of the results measured from it.
+type Wire = (usize, Option<usize>);
+
+// Groups are flattened, so only the operations without children are counted.
+// Columns are visited in order, which keeps the layering of the operations intact.
+// A classically controlled gate waits for the measurement of its control results.
+fn collect_stats(grid: &ComponentGrid, stats: &mut CircuitStats, depths: &mut HashMap<Wire, u64>) {
+    for op in grid.iter().flat_map(|column| column.components.iter()) {
+        let children = match op {
+            Operation::Unitary(u) => &u.children,
+            Operation::Measurement(m) => &m.children,
+            Operation::Ket(k) => &k.children,
+        };
+        if !children.is_empty() {
+            collect_stats(children, stats, depths);
+            continue;
+        }
+
+        let (qubits, results_read, results_written) = match op {
+            Operation::Unitary(u) => {
+                let qubits = quantum_qubits(u.targets.iter().chain(&u.controls));
+                let name = u.gate.rsplit('.').next().unwrap_or(&u.gate);
+
+                stats.gate_count += 1;
+                *stats.gate_counts.entry(name.to_string()).or_default() += 1;
+                if qubits.len() == 2 {
+                    stats.two_qubit_gate_count += 1;
+                }
+                if name == "T" && u.controls.is_empty() {
+                    stats.t_count += 1;
+                }
+                (qubits, result_wires(u.controls.iter()), Vec::new())
+            }
+            Operation::Measurement(m) => {
+                stats.measurement_count += 1;
+                (quantum_qubits(m.qubits.iter()), Vec::new(), result_wires(m.results.iter()))
+            }
+            Operation::Ket(k) => (quantum_qubits(k.targets.iter()), Vec::new(), Vec::new()),
+        };
+
+        let layer = qubits
+            .iter()
+            .map(|&q| (q, None))
+            .chain(results_read)
+            .map(|wire| depths.get(&wire).copied().unwrap_or(0))
+            .max()
+            .unwrap_or(0)
+            + 1;
+        for wire in qubits.into_iter().map(|q| (q, None)).chain(results_written) {
+            depths.insert(wire, layer);
+        }
+    }
+}
+
+// Classical controls refer to result registers and don't occupy a qubit.
+fn quantum_qubits<'a>(registers: impl Iterator<Item = &'a Register>) -> BTreeSet<usize> {
+    registers
+        .filter(|reg| reg.result.is_none())
+        .map(|reg| reg.qubit)
+        .collect()
+}
+
+fn result_wires<'a>(registers: impl Iterator<Item = &'a Register>) -> Vec<Wire> {
+    registers
+        .filter(|reg| reg.result.is_some())
+        .map(|reg| (reg.qubit, reg.result))
+        .collect()
+}
@@ -0,0 +1,383 @@
+use qsc::interpret::CircuitEntryPoint;
+use qsc_circuit::{Circuit, ComponentGrid, Operation, Register};
+use std::collections::HashMap;
+
+use crate::circuit::{generate_circuit, top_level_columns, CircuitGenerationOptions};
+use crate::sim::QsError;
+
+#[derive(Clone, Debug, Default)]
+pub struct SvgGenerationOptions {
+    pub circuit: CircuitGenerationOptions,
+}
+
+const MARGIN: f64 = 10.0;
+const LABEL_WIDTH: f64 = 40.0;
+const WIRE_TAIL: f64 = 20.0;
+const ROW_HEIGHT: f64 = 60.0;
+const CLASSICAL_ROW_HEIGHT: f64 = 30.0;
+const GATE_SIZE: f64 = 36.0;
+const COLUMN_PADDING: f64 = 10.0;
+const GROUP_PADDING: f64 = 8.0;
+const CHAR_WIDTH: f64 = 8.0;
+const CONTROL_RADIUS: f64 = 4.0;
+const TARGET_RADIUS: f64 = 11.0;
+const SWAP_SIZE: f64 = 6.0;
+const CLASSICAL_GAP: f64 = 1.5;
+
+type WireMap = HashMap<(usize, Option<usize>), f64>;
+
+pub fn circuit_svg(source: &str, options: SvgGenerationOptions) -> Result<String, QsError> {
+    generate_svg_circuit(source, CircuitEntryPoint::EntryPoint, options)
+}
+
+pub fn circuit_svg_operation(operation: &str, source: &str, options: SvgGenerationOptions) -> Result<String, QsError> {
+    generate_svg_circuit(source, CircuitEntryPoint::Operation(operation.to_string()), options)
+}
+
+fn generate_svg_circuit(
+    source: &str,
+    entry_point: CircuitEntryPoint,
+    options: SvgGenerationOptions,
+) -> Result<String, QsError> {
+    let circuit = generate_circuit(source, entry_point, &options.circuit)?;
+
+    Ok(circuit_to_svg(&circuit))
+}
+
+pub fn circuit_to_svg(c: &Circuit) -> String {
+    let (wires, height) = layout_wires(c);
+    let columns = top_level_columns(c);
+
+    let mut renderer = SvgRenderer {
+        wires: &wires,
+        elements: Vec::new(),
+        classical_starts: HashMap::new(),
+    };
+    let grid_start = MARGIN + LABEL_WIDTH;
+    let grid_end = renderer.draw_columns(&columns, grid_start);
+    let wire_end = grid_end + WIRE_TAIL;
+    let width = wire_end + MARGIN;
+
+    let mut out = String::new();
+    out.push_str(&format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"14\">\n",
+        w = width,
+        h = height
+    ));
+    out.push_str(&format!(
+        "<rect x=\"0\" y=\"0\" width=\"{}\" height=\"{}\" fill=\"white\"/>\n",
+        width, height
+    ));
+
+    for q in &c.qubits {
+        let y = wires[&(q.id, None)];
+        out.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">q{}</text>\n",
+            grid_start - 6.0,
+            y + 5.0,
+            q.id
+        ));
+        out.push_str(&line(grid_start, y, wire_end, y));
+
+        for r in 0..q.num_results {
+            if let Some(&start) = renderer.classical_starts.get(&(q.id, r)) {
+                let y = wires[&(q.id, Some(r))];
+                out.push_str(&double_horizontal_line(start, wire_end, y));
+            }
+        }
+    }
+
+    for element in &renderer.elements {
+        out.push_str(element);
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+fn layout_wires(c: &Circuit) -> (WireMap, f64) {
+    let mut wires = HashMap::new();
+    let mut y = MARGIN;
+
+    for q in &c.qubits {
+        wires.insert((q.id, None), y + ROW_HEIGHT / 2.0);
+        y += ROW_HEIGHT;
+
+        // Every measurement result gets its own classical wire below the qubit
+        for r in 0..q.num_results {
+            wires.insert((q.id, Some(r)), y + CLASSICAL_ROW_HEIGHT / 2.0);
+            y += CLASSICAL_ROW_HEIGHT;
+        }
+    }
+
+    (wires, y + MARGIN)
+}
+
+struct SvgRenderer<'a> {
+    wires: &'a WireMap,
+    elements: Vec<String>,
+    classical_starts: HashMap<(usize, usize), f64>,
+}
+
+impl SvgRenderer<'_> {
+    fn draw_columns(&mut self, columns: &[Vec<&Operation>], start: f64) -> f64 {
+        let mut x = start;
+        for col in columns {
+            let width = column_width(col.iter().copied());
+            for op in col {
+                self.draw_operation(op, x, width);
+            }
+            x += width;
+        }
+        x
+    }
+
+    fn draw_operation(&mut self, op: &Operation, x: f64, column_width: f64) {
+        let children = operation_children(op);
+        if !children.is_empty() {
+            let width = operation_width(op);
+            self.draw_group(op, children, x + (column_width - width) / 2.0, width);
+            return;
+        }
+
+        let cx = x + column_width / 2.0;
+        match op {
+            Operation::Unitary(u) => {
+                let targets = self.wire_positions(&u.targets);
+                let controls: Vec<(f64, bool)> = u
+                    .controls
+                    .iter()
+                    .filter_map(|reg| self.wire_y(reg).map(|y| (y, reg.result.is_some())))
+                    .collect();
+                let label = gate_label(simple_name(&u.gate), u.is_adjoint);
+                self.draw_unitary(&label, &u.args, cx, &targets, &controls);
+            }
+            Operation::Measurement(m) => {
+                let qubits = self.wire_positions(&m.qubits);
+                for &y in &qubits {
+                    self.draw_meter(cx, y);
+                }
+                let Some(&last_qubit) = qubits.last() else {
+                    return;
+                };
+                let meter_bottom = last_qubit + GATE_SIZE / 2.0;
+                for reg in &m.results {
+                    if let (Some(r), Some(y)) = (reg.result, self.wire_y(reg)) {
+                        self.classical_starts.entry((reg.qubit, r)).or_insert(cx);
+                        self.elements.push(double_vertical_line(cx, meter_bottom, y));
+                        self.elements.push(circle(cx, y, CONTROL_RADIUS, "black"));
+                    }
+                }
+            }
+            Operation::Ket(k) => {
+                for y in self.wire_positions(&k.targets) {
+                    self.draw_box(cx, y, y, GATE_SIZE, "|0⟩", &[]);
+                }
+            }
+        }
+    }
+
+    fn draw_group(&mut self, op: &Operation, children: &ComponentGrid, x: f64, width: f64) {
+        let (name, registers) = match op {
+            Operation::Unitary(u) => (&u.gate, [u.targets.as_slice(), u.controls.as_slice()].concat()),
+            Operation::Measurement(m) => (&m.gate, [m.qubits.as_slice(), m.results.as_slice()].concat()),
+            Operation::Ket(k) => (&k.gate, k.targets.clone()),
+        };
+        let rows = self.wire_positions(&registers);
+        if let (Some(&top), Some(&bottom)) = (rows.first(), rows.last()) {
+            let top = top - ROW_HEIGHT / 2.0 + 2.0;
+            let bottom = bottom + ROW_HEIGHT / 2.0 - 2.0;
+            self.elements.push(format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"gray\" stroke-dasharray=\"4 2\"/>\n",
+                x + 2.0,
+                top,
+                width - 4.0,
+                bottom - top
+            ));
+            self.elements.push(format!(
+                "<text x=\"{}\" y=\"{}\" font-size=\"10\" fill=\"gray\">{}</text>\n",
+                x + 5.0,
+                top + 9.0,
+                escape(simple_name(name))
+            ));
+        }
+
+        let columns: Vec<Vec<&Operation>> = children
+            .iter()
+            .map(|col| col.components.iter().collect())
+            .collect();
+        self.draw_columns(&columns, x + GROUP_PADDING);
+    }
+
+    fn draw_unitary(&mut self, label: &str, args: &[String], cx: f64, targets: &[f64], controls: &[(f64, bool)]) {
+        let (Some(&first_target), Some(&last_target)) = (targets.first(), targets.last()) else {
+            return;
+        };
+        let name = label.trim_end_matches('†');
+        let is_swap = name.eq_ignore_ascii_case("swap") && targets.len() == 2;
+        let is_controlled = !controls.is_empty() && targets.len() == 1;
+
+        for &(y, is_classical) in controls {
+            let target_y = if y < first_target { first_target } else { last_target };
+            if is_classical {
+                self.elements.push(double_vertical_line(cx, y, target_y));
+            } else {
+                self.elements.push(line(cx, y, cx, target_y));
+            }
+            self.elements.push(circle(cx, y, CONTROL_RADIUS, "black"));
+        }
+
+        if is_swap {
+            self.elements.push(line(cx, first_target, cx, last_target));
+            for &y in targets {
+                self.elements.push(line(cx - SWAP_SIZE, y - SWAP_SIZE, cx + SWAP_SIZE, y + SWAP_SIZE));
+                self.elements.push(line(cx - SWAP_SIZE, y + SWAP_SIZE, cx + SWAP_SIZE, y - SWAP_SIZE));
+            }
+        } else if is_controlled && (name == "X" || name == "CNOT" || name == "CCNOT") {
+            self.elements.push(circle(cx, first_target, TARGET_RADIUS, "white"));
+            self.elements.push(line(cx - TARGET_RADIUS, first_target, cx + TARGET_RADIUS, first_target));
+            self.elements.push(line(cx, first_target - TARGET_RADIUS, cx, first_target + TARGET_RADIUS));
+        } else if is_controlled && name == "Z" {
+            self.elements.push(circle(cx, first_target, CONTROL_RADIUS, "black"));
+        } else {
+            self.draw_box(cx, first_target, last_target, box_width(label, args), label, args);
+        }
+    }
+
+    fn draw_meter(&mut self, cx: f64, y: f64) {
+        self.draw_box(cx, y, y, GATE_SIZE, "", &[]);
+        let radius = GATE_SIZE / 2.0 - 6.0;
+        self.elements.push(format!(
+            "<path d=\"M {} {} A {r} {r} 0 0 1 {} {}\" fill=\"none\" stroke=\"black\"/>\n",
+            cx - radius,
+            y + 6.0,
+            cx + radius,
+            y + 6.0,
+            r = radius
+        ));
+        self.elements.push(line(cx, y + 6.0, cx + radius - 2.0, y - radius + 4.0));
+    }
+
+    fn draw_box(&mut self, cx: f64, top_wire: f64, bottom_wire: f64, width: f64, label: &str, args: &[String]) {
+        let top = top_wire - GATE_SIZE / 2.0;
+        let bottom = bottom_wire + GATE_SIZE / 2.0;
+        let cy = (top + bottom) / 2.0;
+        self.elements.push(format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"white\" stroke=\"black\"/>\n",
+            cx - width / 2.0,
+            top,
+            width,
+            bottom - top
+        ));
+
+        if label.is_empty() {
+            return;
+        }
+        if args.is_empty() {
+            self.elements.push(text(cx, cy + 5.0, 14.0, label));
+        } else {
+            self.elements.push(text(cx, cy - 2.0, 14.0, label));
+            self.elements.push(text(cx, cy + 12.0, 10.0, &args.join(", ")));
+        }
+    }
+
+    fn wire_y(&self, reg: &Register) -> Option<f64> {
+        self.wires.get(&(reg.qubit, reg.result)).copied()
+    }
+
+    fn wire_positions(&self, registers: &[Register]) -> Vec<f64> {
+        let mut positions: Vec<f64> = registers.iter().filter_map(|reg| self.wire_y(reg)).collect();
+        positions.sort_by(f64::total_cmp);
+        positions.dedup();
+        positions
+    }
+}
+
+fn operation_children(op: &Operation) -> &ComponentGrid {
+    match op {
+        Operation::Unitary(u) => &u.children,
+        Operation::Measurement(m) => &m.children,
+        Operation::Ket(k) => &k.children,
+    }
+}
+
+fn column_width<'a>(ops: impl Iterator<Item = &'a Operation>) -> f64 {
+    ops.map(operation_width).fold(0.0, f64::max)
+}
+
+fn operation_width(op: &Operation) -> f64 {
+    let children = operation_children(op);
+    if !children.is_empty() {
+        let inner: f64 = children
+            .iter()
+            .map(|col| column_width(col.components.iter()))
+            .sum();
+        return inner + 2.0 * GROUP_PADDING;
+    }
+
+    let width = match op {
+        Operation::Unitary(u) => box_width(&gate_label(simple_name(&u.gate), u.is_adjoint), &u.args),
+        _ => GATE_SIZE,
+    };
+    width + 2.0 * COLUMN_PADDING
+}
+
+fn box_width(label: &str, args: &[String]) -> f64 {
+    let label_width = label.chars().count() as f64 * CHAR_WIDTH;
+    let args_width = args.join(", ").chars().count() as f64 * CHAR_WIDTH * 0.75;
+    GATE_SIZE.max(label_width.max(args_width) + 12.0)
+}
+
+fn simple_name(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+fn gate_label(name: &str, is_adjoint: bool) -> String {
+    if is_adjoint {
+        format!("{}†", name)
+    } else {
+        name.to_string()
+    }
+}
+
+fn line(x1: f64, y1: f64, x2: f64, y2: f64) -> String {
+    format!(
+        "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"black\"/>\n",
+        x1, y1, x2, y2
+    )
+}
+
+fn double_horizontal_line(x1: f64, x2: f64, y: f64) -> String {
+    let mut out = line(x1, y - CLASSICAL_GAP, x2, y - CLASSICAL_GAP);
+    out.push_str(&line(x1, y + CLASSICAL_GAP, x2, y + CLASSICAL_GAP));
+    out
+}
+
+fn double_vertical_line(x: f64, y1: f64, y2: f64) -> String {
+    let mut out = line(x - CLASSICAL_GAP, y1, x - CLASSICAL_GAP, y2);
+    out.push_str(&line(x + CLASSICAL_GAP, y1, x + CLASSICAL_GAP, y2));
+    out
+}
+
+fn circle(cx: f64, cy: f64, r: f64, fill: &str) -> String {
+    format!(
+        "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\" stroke=\"black\"/>\n",
+        cx, cy, r, fill
+    )
+}
+
+fn text(x: f64, y: f64, size: f64, content: &str) -> String {
+    format!(
+        "<text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"middle\">{}</text>\n",
+        x,
+        y,
+        size,
+        escape(content)
+    )
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use qsc::{PackageType, TargetCapabilityFlags};
+
+use crate::noise::Noise;
+use crate::sim::{ExecutionOptions, ExecutionState, QsError, create_interpreter, run_compiled};
+
+/// Decides whether a shot of a noise sweep succeeded, e.g. by comparing its result with the expected one.
+pub trait SuccessPredicate: Send + Sync {
+    fn is_success(&self, shot: ExecutionState) -> bool;
+}
+
+/// The outcome of the shots at one noise point of a sweep.
+pub struct NoiseSweepPoint {
+    pub noise: Noise,
+    /// The seed of the simulation at this point, derived from the seed of the sweep and the index of the point.
+    pub seed: u64,
+    /// The number of shots by result.
+    pub histogram: HashMap<String, u64>,
+    pub successes: u64,
+    pub success_probability: f64,
+}
+
+/// Runs the program at each noise point, compiling it only once.
+/// Each point uses the base `options` with the noise of the point; gate or qubit specific noise
+/// of the base options still takes precedence over it.
+/// Every point gets its own seed derived from `seed`, so that a sweep can be reproduced.
+pub fn noise_sweep(
+    source: &str,
+    options: Arc<ExecutionOptions>,
+    noise_points: Vec<Noise>,
+    seed: u64,
+    success_predicate: Box<dyn SuccessPredicate>,
+) -> Result<Vec<NoiseSweepPoint>, QsError> {
+    let mut interpreter = create_interpreter(Some(source), PackageType::Exe, TargetCapabilityFlags::all())?;
+
+    let mut points = Vec::new();
+    for (index, noise) in noise_points.into_iter().enumerate() {
+        let seed = point_seed(seed, index as u64);
+        let point_options = ExecutionOptions {
+            noise: noise.clone(),
+            ..options.as_ref().clone()
+        };
+        let results = run_compiled(&mut interpreter, &point_options, Some(seed))?;
+
+        let mut histogram = HashMap::new();
+        let mut successes = 0;
+        for shot in results {
+            *histogram.entry(shot.result.clone().unwrap_or_default()).or_insert(0) += 1;
+            if success_predicate.is_success(shot) {
+                successes += 1;
+            }
+        }
+
+        points.push(NoiseSweepPoint {
+            noise,
+            seed,
+            histogram,
+            successes,
+            success_probability: if options.shots == 0 { 0.0 } else { successes as f64 / options.shots as f64 },
+        });
+    }
+    Ok(points)
+}
+
+// SplitMix64 seeded with the seed of the sweep, so that neighbouring points get unrelated seeds.
+fn point_seed(seed: u64, index: u64) -> u64 {
+    let mut z = seed.wrapping_add(index.wrapping_add(1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
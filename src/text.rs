@@ -0,0 +1,421 @@
+use qsc::interpret::CircuitEntryPoint;
+use qsc_circuit::{Circuit, Operation, Register};
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::circuit::{generate_circuit, top_level_columns, CircuitGenerationOptions};
+use crate::sim::QsError;
+
+#[derive(Clone, Debug, Default)]
+pub struct TextGenerationOptions {
+    pub circuit: CircuitGenerationOptions,
+    /// Folds the circuit so that no line is wider than this many characters.
+    pub max_width: Option<u32>,
+}
+
+type RegisterMap = HashMap<(usize, Option<usize>), usize>;
+
+// The wire drawn on a row. Result rows have no wire until the measurement that writes them.
+#[derive(Clone, Copy, PartialEq)]
+enum Wire {
+    Quantum,
+    Classical,
+    Empty,
+}
+
+pub fn circuit_text(source: &str, options: TextGenerationOptions) -> Result<String, QsError> {
+    generate_text_circuit(source, CircuitEntryPoint::EntryPoint, options)
+}
+
+pub fn circuit_text_operation(operation: &str, source: &str, options: TextGenerationOptions) -> Result<String, QsError> {
+    generate_text_circuit(source, CircuitEntryPoint::Operation(operation.to_string()), options)
+}
+
+fn generate_text_circuit(
+    source: &str,
+    entry_point: CircuitEntryPoint,
+    options: TextGenerationOptions,
+) -> Result<String, QsError> {
+    let circuit = generate_circuit(source, entry_point, &options.circuit)?;
+
+    Ok(circuit_to_text(&circuit, &options))
+}
+
+pub fn circuit_to_text(c: &Circuit, options: &TextGenerationOptions) -> String {
+    let mut register_to_row = HashMap::new();
+    let mut labels = Vec::new();
+    let mut wires = Vec::new();
+    for q in &c.qubits {
+        register_to_row.insert((q.id, None), labels.len());
+        labels.push(format!("q{}: ", q.id));
+        wires.push(Wire::Quantum);
+
+        // each result of the qubit gets its own classical row right below it, as in Quantikz
+        for i in 0..q.num_results {
+            register_to_row.insert((q.id, Some(i)), labels.len());
+            labels.push(format!("c{},{}: ", q.id, i));
+            wires.push(Wire::Empty);
+        }
+    }
+
+    let columns: Vec<Vec<Cell>> = top_level_columns(c)
+        .iter()
+        .map(|ops| render_column(ops, &register_to_row, &mut wires))
+        .collect();
+
+    render_lines(&labels, &columns, &wires, options.max_width)
+}
+
+// A single row of a column, made of the line above the wire, the wire and the line below it.
+#[derive(Clone)]
+struct Cell {
+    top: String,
+    mid: String,
+    bottom: String,
+}
+
+impl Cell {
+    fn width(&self) -> usize {
+        self.mid.chars().count()
+    }
+}
+
+fn render_column(ops: &[&Operation], register_to_row: &RegisterMap, wires: &mut [Wire]) -> Vec<Cell> {
+    let before = wires.to_vec();
+    let mut glyphs: Vec<Option<Glyph>> = vec![None; wires.len()];
+
+    for op in ops {
+        place_operation(op, register_to_row, &before, &mut glyphs, wires);
+    }
+
+    let width = glyphs
+        .iter()
+        .flatten()
+        .map(|glyph| glyph.cell().width())
+        .max()
+        .unwrap_or(1);
+
+    glyphs
+        .iter()
+        .enumerate()
+        .map(|(row, glyph)| {
+            let left = wire_char(before[row]);
+            let right = wire_char(wires[row]);
+            match glyph {
+                Some(glyph) => pad(glyph.cell(), width + 2, left, right),
+                None => pad(
+                    Cell {
+                        top: String::new(),
+                        mid: String::new(),
+                        bottom: String::new(),
+                    },
+                    width + 2,
+                    left,
+                    left,
+                ),
+            }
+        })
+        .collect()
+}
+
+fn place_operation(
+    op: &Operation,
+    register_to_row: &RegisterMap,
+    before: &[Wire],
+    glyphs: &mut [Option<Glyph>],
+    wires: &mut [Wire],
+) {
+    let no_registers: &[Register] = &[];
+    let (name, targets, controls, results, is_adjoint, has_children) = match op {
+        Operation::Unitary(u) => (&u.gate, &u.targets[..], &u.controls[..], no_registers, u.is_adjoint, !u.children.is_empty()),
+        Operation::Measurement(m) => (&m.gate, &m.qubits[..], no_registers, &m.results[..], false, !m.children.is_empty()),
+        Operation::Ket(k) => (&k.gate, &k.targets[..], no_registers, no_registers, false, !k.children.is_empty()),
+    };
+    let targets = rows_for(targets, register_to_row);
+    let (Some(&first), Some(&last)) = (targets.first(), targets.last()) else {
+        return;
+    };
+    // controls on result rows are classical, they never turn the target into a quantum control symbol
+    let quantum_controls = rows_for(controls.iter().filter(|reg| reg.result.is_none()), register_to_row);
+    let classical_controls = rows_for(controls.iter().filter(|reg| reg.result.is_some()), register_to_row);
+    // a measurement is linked to the rows of its results
+    let results = if has_children { Vec::new() } else { rows_for(results, register_to_row) };
+    let links: Vec<usize> = quantum_controls.iter().chain(&classical_controls).chain(&results).copied().collect();
+    let classical_link = quantum_controls.is_empty() && !links.is_empty();
+
+    let simple_name = name.rsplit('.').next().unwrap_or(name);
+    let span_top = links.iter().copied().fold(first, usize::min);
+    let span_bottom = links.iter().copied().fold(last, usize::max);
+
+    let is_swap = simple_name.eq_ignore_ascii_case("swap") && targets.len() == 2;
+    let is_controlled = !quantum_controls.is_empty() && targets.len() == 1 && !has_children;
+
+    // the glyph of a linked row that is not a target, or a crossing of the link
+    let link_glyph = |row: usize| {
+        let up = row > span_top;
+        let down = row < span_bottom;
+        if quantum_controls.contains(&row) {
+            Glyph::Symbol { symbol: '●', up, down, classical: classical_link }
+        } else if classical_controls.contains(&row) {
+            Glyph::Symbol { symbol: '■', up, down, classical: classical_link }
+        } else if results.contains(&row) {
+            // the classical wire of the result starts at its measurement
+            let symbol = if before[row] == Wire::Classical { '╩' } else { '╚' };
+            Glyph::Symbol { symbol, up, down, classical: true }
+        } else {
+            Glyph::Crossing { wire: before[row], classical: classical_link }
+        }
+    };
+
+    if is_swap || (is_controlled && matches!(simple_name, "X" | "CNOT" | "CCNOT" | "Z")) {
+        let symbol = if is_swap {
+            '╳'
+        } else if simple_name == "Z" {
+            '●'
+        } else {
+            '⊕'
+        };
+        for (row, glyph) in (span_top..).zip(&mut glyphs[span_top..=span_bottom]) {
+            *glyph = Some(if targets.contains(&row) {
+                Glyph::Symbol { symbol, up: row > span_top, down: row < span_bottom, classical: classical_link }
+            } else {
+                link_glyph(row)
+            });
+        }
+        return;
+    }
+
+    let label = match op {
+        Operation::Measurement(_) if !has_children => "M".to_string(),
+        Operation::Ket(_) if !has_children => "|0⟩".to_string(),
+        _ => operation_label(simple_name, &op.args(), is_adjoint),
+    };
+
+    for (row, glyph) in (span_top..).zip(&mut glyphs[span_top..=span_bottom]) {
+        *glyph = Some(if row >= first && row <= last {
+            Glyph::Box {
+                label: if row == first { label.clone() } else { String::new() },
+                inner_width: label.chars().count(),
+                is_first: row == first,
+                is_last: row == last,
+                up: row == first && span_top < first,
+                down: row == last && span_bottom > last,
+                classical: classical_link,
+            }
+        } else {
+            link_glyph(row)
+        });
+    }
+
+    for &row in &results {
+        wires[row] = Wire::Classical;
+    }
+}
+
+#[derive(Clone)]
+enum Glyph {
+    Box {
+        label: String,
+        inner_width: usize,
+        is_first: bool,
+        is_last: bool,
+        up: bool,
+        down: bool,
+        classical: bool,
+    },
+    Symbol {
+        symbol: char,
+        up: bool,
+        down: bool,
+        classical: bool,
+    },
+    // A wire crossed by the vertical link of an operation, `classical` for a classical link.
+    Crossing {
+        wire: Wire,
+        classical: bool,
+    },
+}
+
+impl Glyph {
+    fn cell(&self) -> Cell {
+        match self {
+            Glyph::Box {
+                label,
+                inner_width,
+                is_first,
+                is_last,
+                up,
+                down,
+                classical,
+            } => {
+                let (up_junction, down_junction) = if *classical { ('╨', '╥') } else { ('┴', '┬') };
+                let top = if *is_first {
+                    format!("┌{}┐", border(*inner_width, *up, up_junction))
+                } else {
+                    format!("│{}│", " ".repeat(*inner_width))
+                };
+                let bottom = if *is_last {
+                    format!("└{}┘", border(*inner_width, *down, down_junction))
+                } else {
+                    format!("│{}│", " ".repeat(*inner_width))
+                };
+                let padding = inner_width - label.chars().count();
+                let mid = format!(
+                    "┤{}{}{}├",
+                    " ".repeat(padding / 2),
+                    label,
+                    " ".repeat(padding - padding / 2)
+                );
+                Cell { top, mid, bottom }
+            }
+            Glyph::Symbol { symbol, up, down, classical } => Cell {
+                top: connector(*up, *classical),
+                mid: symbol.to_string(),
+                bottom: connector(*down, *classical),
+            },
+            Glyph::Crossing { wire, classical } => {
+                let mid = match (wire, classical) {
+                    (Wire::Quantum, false) => '┼',
+                    (Wire::Classical, false) => '╪',
+                    (Wire::Quantum, true) => '╫',
+                    (Wire::Classical, true) => '╬',
+                    (Wire::Empty, _) => vertical(*classical),
+                };
+                Cell {
+                    top: vertical(*classical).to_string(),
+                    mid: mid.to_string(),
+                    bottom: vertical(*classical).to_string(),
+                }
+            }
+        }
+    }
+}
+
+fn border(width: usize, connected: bool, junction: char) -> String {
+    let mut chars: Vec<char> = vec!['─'; width];
+    if connected && width > 0 {
+        chars[(width - 1) / 2] = junction;
+    }
+    chars.into_iter().collect()
+}
+
+fn connector(connected: bool, classical: bool) -> String {
+    if connected { vertical(classical) } else { ' ' }.to_string()
+}
+
+fn vertical(classical: bool) -> char {
+    if classical { '║' } else { '│' }
+}
+
+fn wire_char(wire: Wire) -> char {
+    match wire {
+        Wire::Quantum => '─',
+        Wire::Classical => '═',
+        Wire::Empty => ' ',
+    }
+}
+
+fn pad(cell: Cell, width: usize, left_wire: char, right_wire: char) -> Cell {
+    let total = width.saturating_sub(cell.width());
+    let left = total / 2;
+    let right = total - left;
+    let spaces = |s: &str| format!("{}{}{}", " ".repeat(left), s, " ".repeat(right));
+
+    Cell {
+        top: spaces(&cell.top),
+        mid: format!(
+            "{}{}{}",
+            left_wire.to_string().repeat(left),
+            cell.mid,
+            right_wire.to_string().repeat(right)
+        ),
+        bottom: spaces(&cell.bottom),
+    }
+}
+
+fn render_lines(labels: &[String], columns: &[Vec<Cell>], wires: &[Wire], max_width: Option<u32>) -> String {
+    let label_width = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let segments = fold_columns(columns, label_width, max_width);
+
+    let mut out = String::new();
+    for (segment_idx, segment) in segments.iter().enumerate() {
+        if segment_idx > 0 {
+            out.push('\n');
+        }
+        let is_continuation = segment_idx > 0;
+        let is_continued = segment_idx + 1 < segments.len();
+
+        for (row, label) in labels.iter().enumerate() {
+            let mut top = " ".repeat(label_width);
+            let mut mid = format!("{:<width$}", label, width = label_width);
+            let mut bottom = " ".repeat(label_width);
+
+            if is_continuation {
+                top.push(' ');
+                mid.push('«');
+                bottom.push(' ');
+            }
+            for column in &columns[segment.clone()] {
+                top.push_str(&column[row].top);
+                mid.push_str(&column[row].mid);
+                bottom.push_str(&column[row].bottom);
+            }
+            if is_continued {
+                mid.push('»');
+            } else {
+                mid.push(wire_char(wires[row]));
+            }
+
+            for line in [top, mid, bottom] {
+                out.push_str(line.trim_end());
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+// Greedily packs columns into segments that fit into `max_width` characters,
+// keeping at least one column per segment.
+fn fold_columns(columns: &[Vec<Cell>], label_width: usize, max_width: Option<u32>) -> Vec<Range<usize>> {
+    // one character each for the continuation markers on both ends
+    let available = max_width
+        .filter(|&w| w > 0)
+        .map_or(usize::MAX, |w| (w as usize).saturating_sub(label_width + 2));
+
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut used = 0;
+    for (idx, column) in columns.iter().enumerate() {
+        let width = column.first().map_or(0, Cell::width);
+        if idx > start && used + width > available {
+            segments.push(start..idx);
+            start = idx;
+            used = 0;
+        }
+        used += width;
+    }
+    segments.push(start..columns.len());
+    segments
+}
+
+fn operation_label(name: &str, args: &[String], is_adjoint: bool) -> String {
+    let mut label = name.to_string();
+    if is_adjoint {
+        label.push('†');
+    }
+    if !args.is_empty() {
+        label = format!("{}({})", label, args.join(", "));
+    }
+    label
+}
+
+fn rows_for<'a>(registers: impl IntoIterator<Item = &'a Register>, register_to_row: &RegisterMap) -> Vec<usize> {
+    let mut rows: Vec<usize> = registers
+        .into_iter()
+        .filter_map(|reg| register_to_row.get(&(reg.qubit, reg.result)).copied())
+        .collect();
+    rows.sort();
+    rows.dedup();
+    rows
+}
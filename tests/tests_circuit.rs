@@ -0,0 +1,168 @@
+use qsharp_bridge::circuit::{circuit_json, circuit_json_operation, CircuitGenerationOptions};
+use qsharp_bridge::circuit_model::{circuit, circuit_operation, CircuitOperation, CircuitRegister};
+use qsharp_bridge::sim::QsError;
+use serde_json::{Value, from_str};
+
+#[test]
+fn circuit_json_one_gate() {
+    let json = circuit_json(
+        r"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Unit {
+                    use q = Qubit();
+                    H(q);
+                    M(q);
+                }
+            }
+        ",
+        CircuitGenerationOptions { group_by_scope: false, ..Default::default() }
+    ).expect("circuit generation should succeed");
+
+    let v: Value = from_str(&json).unwrap();
+    assert_eq!(v["version"].as_u64(), Some(1));
+    assert_eq!(v["circuits"].as_array().map_or(0, |c| c.len()), 1);
+
+    let circuit = &v["circuits"][0];
+    assert_eq!(circuit["qubits"].as_array().map_or(0, |q| q.len()), 1);
+    assert_eq!(circuit["componentGrid"].as_array().map_or(0, |g| g.len()), 2);
+    assert_eq!(circuit["componentGrid"][0]["components"][0]["gate"].as_str(), Some("H"));
+}
+
+#[test]
+fn circuit_json_operation_toffoli() {
+    let source = r"
+        namespace Test {
+            operation Main() : Unit {
+                use q = Qubit[3];
+                CCNOT(q[0], q[1], q[2]);
+            }
+        }
+    ";
+
+    let json = circuit_json_operation("Test.Main", source, CircuitGenerationOptions { group_by_scope: false, ..Default::default() }).expect("circuit generation should succeed");
+
+    let v: Value = from_str(&json).unwrap();
+    let circuit = &v["circuits"][0];
+    assert_eq!(circuit["qubits"].as_array().map_or(0, |q| q.len()), 3);
+
+    let toffoli = &circuit["componentGrid"][0]["components"][0];
+    assert_eq!(toffoli["controls"].as_array().map_or(0, |c| c.len()), 2);
+    assert_eq!(toffoli["targets"].as_array().map_or(0, |t| t.len()), 1);
+}
+
+#[test]
+fn circuit_model_one_gate() {
+    let c = circuit(
+        r"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Unit {
+                    use q = Qubit();
+                    H(q);
+                    M(q);
+                }
+            }
+        ",
+        CircuitGenerationOptions { group_by_scope: false, ..Default::default() }
+    ).expect("circuit generation should succeed");
+
+    assert_eq!(c.qubits.len(), 1);
+    assert_eq!(c.qubits[0].id, 0);
+    assert_eq!(c.qubits[0].num_results, 1);
+    assert_eq!(c.columns.len(), 2);
+
+    match &c.columns[0].operations[0] {
+        CircuitOperation::Unitary { gate, is_adjoint, targets, controls, .. } => {
+            assert_eq!(gate, "H");
+            assert!(!is_adjoint);
+            assert_eq!(targets, &vec![CircuitRegister { qubit: 0, result: None }]);
+            assert!(controls.is_empty());
+        }
+        other => panic!("Expected a unitary, got {:?}", other),
+    }
+
+    match &c.columns[1].operations[0] {
+        CircuitOperation::Measurement { qubits, results, .. } => {
+            assert_eq!(qubits, &vec![CircuitRegister { qubit: 0, result: None }]);
+            assert_eq!(results, &vec![CircuitRegister { qubit: 0, result: Some(0) }]);
+        }
+        other => panic!("Expected a measurement, got {:?}", other),
+    }
+}
+
+#[test]
+fn circuit_model_operation_grouped() {
+    let source = r"
+        namespace Test {
+            operation PrepareBellState(q1 : Qubit, q2: Qubit) : Unit {
+                H(q1);
+                CNOT(q1, q2);
+            }
+
+            operation Main() : Unit {
+                use (q1, q2) = (Qubit(), Qubit());
+                PrepareBellState(q1, q2);
+                Reset(q1);
+                Reset(q2);
+            }
+        }
+    ";
+
+    let c = circuit_operation("Test.Main", source, CircuitGenerationOptions { group_by_scope: true, ..Default::default() }).expect("circuit generation should succeed");
+
+    fn find_group(columns: &[qsharp_bridge::circuit_model::CircuitColumn]) -> bool {
+        columns.iter().flat_map(|col| &col.operations).any(|op| match op {
+            CircuitOperation::Unitary { gate, children, .. } => (gate.ends_with("PrepareBellState") && !children.is_empty()) || find_group(children),
+            _ => false,
+        })
+    }
+
+    assert_eq!(c.qubits.len(), 2);
+    assert!(find_group(&c.columns), "Should contain the PrepareBellState group with its children");
+}
+
+#[test]
+fn circuit_max_depth_keeps_result_wires_of_collapsed_groups() {
+    let source = r"
+        namespace Test {
+            operation MeasureBellState(q1 : Qubit, q2: Qubit) : Result {
+                H(q1);
+                CNOT(q1, q2);
+                M(q2)
+            }
+
+            operation Main() : Result {
+                use (q1, q2) = (Qubit(), Qubit());
+                let r = MeasureBellState(q1, q2);
+                ResetAll([q1, q2]);
+                r
+            }
+        }
+    ";
+    let options = CircuitGenerationOptions { group_by_scope: true, max_depth: Some(1), ..Default::default() };
+
+    let c = circuit_operation("Test.Main", source, options).expect("circuit generation should succeed");
+
+    let group = c
+        .columns
+        .iter()
+        .flat_map(|col| &col.operations)
+        .find_map(|op| match op {
+            CircuitOperation::Unitary { gate, children, targets, .. } if gate.ends_with("MeasureBellState") => Some((children, targets)),
+            _ => None,
+        })
+        .expect("should contain the MeasureBellState group");
+    let (children, targets) = group;
+    assert!(children.is_empty(), "the group should be collapsed");
+    assert!(targets.contains(&CircuitRegister { qubit: 1, result: Some(0) }));
+}
+
+#[test]
+fn circuit_max_depth_rejects_zero() {
+    let options = CircuitGenerationOptions { group_by_scope: true, max_depth: Some(0), ..Default::default() };
+
+    let error = circuit_json("operation Main() : Unit {}", options).unwrap_err();
+
+    assert!(matches!(error, QsError::InvalidParameter { parameter, .. } if parameter == "max_depth"));
+}
@@ -0,0 +1,177 @@
+use qsharp_bridge::counting::{estimate_qasm, estimate_qir, qasm_logical_counts, qir_logical_counts};
+use qsharp_bridge::estimation_result::LogicalCounts;
+use serde_json::{Value, from_str};
+
+const QASM: &str = r#"
+    OPENQASM 2.0;
+    include "qelib1.inc";
+    qreg q[3];
+    creg c[3];
+    // CNOT followed by a rotation and a T gate
+    gate entangle(theta) a, b { cx a, b; rz(theta) b; t a; }
+    h q;
+    t q[0];
+    tdg q[1];
+    rz(0.3) q[2];
+    entangle(0.1) q[0], q[1];
+    ccx q[0], q[1], q[2];
+    measure q -> c;
+"#;
+
+const QIR: &str = r#"
+    define void @ENTRYPOINT__main() #0 {
+    block_0:
+      call void @__quantum__rt__initialize(i8* null)
+      call void @__quantum__qis__h__body(%Qubit* null)
+      call void @__quantum__qis__t__body(%Qubit* inttoptr (i64 1 to %Qubit*))
+      call void @__quantum__qis__rz__body(double 0.5, %Qubit* inttoptr (i64 1 to %Qubit*))
+      call void @__quantum__qis__rz__body(double 0x3FE921FB54442D18, %Qubit* null)
+      call void @__quantum__qis__cx__body(%Qubit* null, %Qubit* inttoptr (i64 1 to %Qubit*))
+      call void @__quantum__qis__ccx__body(%Qubit* null, %Qubit* inttoptr (i64 1 to %Qubit*), %Qubit* inttoptr (i64 2 to %Qubit*))
+      call void @__quantum__qis__mz__body(%Qubit* null, %Result* writeonly null)
+      call void @__quantum__rt__result_record_output(%Result* null, i8* null)
+      ret void
+    }
+    attributes #0 = { "entry_point" "required_num_qubits"="3" "required_num_results"="1" }
+"#;
+
+#[test]
+fn qasm_logical_counts_classifies_gates() {
+    let counts = qasm_logical_counts(QASM).unwrap();
+
+    assert_eq!(
+        counts,
+        LogicalCounts {
+            num_qubits: 3,
+            t_count: 3,
+            rotation_count: 2,
+            rotation_depth: 1,
+            ccz_count: 1,
+            ccix_count: 0,
+            measurement_count: 3,
+        }
+    );
+}
+
+#[test]
+fn qasm_logical_counts_reads_openqasm_3() {
+    let source = r#"
+        OPENQASM 3.0;
+        include "stdgates.inc";
+        qubit[2] q;
+        bit[2] c;
+        inv @ t q[0];
+        for int i in [0:2] { t q[1]; }
+        ctrl @ x q[0], q[1];
+        c = measure q;
+    "#;
+
+    let counts = qasm_logical_counts(source).unwrap();
+
+    assert_eq!(counts.num_qubits, 2);
+    assert_eq!(counts.t_count, 4);
+    assert_eq!(counts.measurement_count, 2);
+}
+
+#[test]
+fn qasm_logical_counts_rejects_invalid_programs() {
+    assert!(qasm_logical_counts("OPENQASM 2.0; qreg q[1]; foo q[0];").is_err());
+    assert!(qasm_logical_counts("OPENQASM 3.0; qubit q; h q").is_err());
+}
+
+#[test]
+fn qir_logical_counts_walks_intrinsics() {
+    let counts = qir_logical_counts(QIR).unwrap();
+
+    assert_eq!(
+        counts,
+        LogicalCounts {
+            num_qubits: 3,
+            t_count: 2,
+            rotation_count: 1,
+            rotation_depth: 1,
+            ccz_count: 1,
+            ccix_count: 0,
+            measurement_count: 1,
+        }
+    );
+}
+
+#[test]
+fn qir_logical_counts_bounds_branches_and_follows_calls() {
+    let ir = r#"
+        define void @main() #0 {
+        entry:
+          call void @__quantum__qis__h__body(%Qubit* null)
+          call void @__quantum__qis__mz__body(%Qubit* null, %Result* writeonly null)
+          %0 = call i1 @__quantum__qis__read_result__body(%Result* null)
+          br i1 %0, label %then, label %else
+
+        then:                                             ; preds = %entry
+          call void @__quantum__qis__t__body(%Qubit* inttoptr (i64 1 to %Qubit*))
+          call void @__quantum__qis__t__body(%Qubit* inttoptr (i64 1 to %Qubit*))
+          br label %continue
+
+        else:                                             ; preds = %entry
+          call void @__quantum__qis__rz__body(double 0.3, %Qubit* inttoptr (i64 1 to %Qubit*))
+          br label %continue
+
+        continue:                                         ; preds = %else, %then
+          call void @rotate(double 0.2, %Qubit* inttoptr (i64 1 to %Qubit*))
+          ret void
+        }
+
+        define internal void @rotate(double %theta, %Qubit* %q) {
+          call void @__quantum__qis__rz__body(double %theta, %Qubit* %q)
+          ret void
+        }
+
+        attributes #0 = { "entry_point" "required_num_qubits"="2" "required_num_results"="1" }
+    "#;
+
+    let counts = qir_logical_counts(ir).unwrap();
+
+    // the larger counts of the two branches, followed by the rotation of `@rotate`
+    assert_eq!(
+        counts,
+        LogicalCounts {
+            num_qubits: 2,
+            t_count: 2,
+            rotation_count: 2,
+            rotation_depth: 2,
+            ccz_count: 0,
+            ccix_count: 0,
+            measurement_count: 1,
+        }
+    );
+}
+
+#[test]
+fn qir_logical_counts_rejects_loops() {
+    let ir = r#"
+        define void @main() #0 {
+        entry:
+          br label %loop
+        loop:
+          call void @__quantum__qis__t__body(%Qubit* null)
+          %0 = call i1 @__quantum__qis__read_result__body(%Result* null)
+          br i1 %0, label %loop, label %exit
+        exit:
+          ret void
+        }
+        attributes #0 = { "entry_point" }
+    "#;
+
+    let error = qir_logical_counts(ir).unwrap_err();
+    assert!(error.to_string().contains("loop"));
+}
+
+#[test]
+fn estimate_qasm_and_qir() {
+    for result in [estimate_qasm(QASM, None).unwrap(), estimate_qir(QIR, None).unwrap()] {
+        let v: Value = from_str(result.as_str()).unwrap();
+        assert_eq!(v[0]["status"].as_str(), Some("success"));
+        assert_eq!(v[0]["logicalCounts"]["numQubits"].as_i64(), Some(3));
+        assert!(v[0]["physicalCounts"]["physicalQubits"].as_i64().is_some_and(|n| n > 0));
+    }
+}
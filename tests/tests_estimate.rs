@@ -1,4 +1,5 @@
-use qsharp_bridge::sim::{estimate, estimate_expression};
+use qsharp_bridge::estimation_result::LogicalCounts;
+use qsharp_bridge::sim::{estimate, estimate_expression, estimate_logical_counts};
 use serde_json::{Value, from_str};
 
 #[test]
@@ -29,4 +30,25 @@ fn test_estimation_expression() {
     assert_eq!(v[0]["jobParams"].is_object(), true);
     assert_eq!(v[0]["physicalCounts"]["physicalQubits"].as_i64().map_or(false, |n| n > 0), true);
     assert_eq!(v[0]["logicalCounts"]["numQubits"].as_i64().map_or(false, |n| n > 0), true);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_estimation_from_logical_counts() {
+    let counts = LogicalCounts {
+        num_qubits: 12,
+        t_count: 100,
+        rotation_count: 20,
+        rotation_depth: 10,
+        ccz_count: 5,
+        ccix_count: 0,
+        measurement_count: 12,
+    };
+    let result = estimate_logical_counts(counts, None).unwrap();
+
+    let v: Value = from_str(result.as_str()).unwrap();
+    assert_eq!(v.as_array().map_or(0, |x| x.len()), 1);
+    assert_eq!(v[0]["status"].as_str(), Some("success"));
+    assert_eq!(v[0]["logicalCounts"]["numQubits"].as_i64(), Some(12));
+    assert_eq!(v[0]["logicalCounts"]["tCount"].as_i64(), Some(100));
+    assert!(v[0]["physicalCounts"]["physicalQubits"].as_i64().map_or(false, |n| n > 0));
+}
@@ -0,0 +1,291 @@
+use qsharp_bridge::estimation::{
+    estimate_with_params, estimator_params_json, CustomQubitParams, DistillationUnitSpecification, ErrorBudget,
+    EstimateType, EstimationConstraints, EstimatorParams, InstructionSet, PredefinedQubitModel, QecScheme, QecSchemeName, QubitModel,
+};
+use qsharp_bridge::estimation_comparison::{
+    comparison_table, estimate_batch, estimate_frontier, pareto_frontier, predefined_estimator_params,
+};
+use qsharp_bridge::estimation_result::{estimate_results, parse_estimation_results, EstimationResult};
+use qsharp_bridge::sim::QsError;
+use serde_json::{Value, from_str};
+
+const SAMPLE_RESULT: &str = include_str!("assets/estimation_result.json");
+
+fn custom_gate_based_qubit() -> CustomQubitParams {
+    CustomQubitParams {
+        name: "my_qubit".to_string(),
+        instruction_set: InstructionSet::GateBased,
+        one_qubit_measurement_time_ns: 100,
+        one_qubit_gate_time_ns: Some(50),
+        two_qubit_gate_time_ns: Some(50),
+        two_qubit_joint_measurement_time_ns: None,
+        t_gate_time_ns: Some(50),
+        one_qubit_measurement_error_rate: 1e-3,
+        one_qubit_gate_error_rate: Some(1e-3),
+        two_qubit_gate_error_rate: Some(1e-3),
+        two_qubit_joint_measurement_error_rate: None,
+        t_gate_error_rate: 1e-3,
+        idle_error_rate: None,
+    }
+}
+
+fn parameter_of(error: QsError) -> String {
+    match error {
+        QsError::InvalidParameter { parameter, .. } => parameter,
+        other => panic!("expected an invalid parameter error, got {other:?}"),
+    }
+}
+
+#[test]
+fn estimator_params_json_serializes_the_estimator_schema() {
+    let params = EstimatorParams {
+        estimate_type: Some(EstimateType::Frontier),
+        qubit_model: Some(QubitModel::Predefined { model: PredefinedQubitModel::MajNsE6 }),
+        qec_scheme: Some(QecScheme {
+            name: QecSchemeName::FloquetCode,
+            error_correction_threshold: None,
+            crossing_prefactor: None,
+            logical_cycle_time: None,
+            physical_qubits_per_logical_qubit: None,
+            max_code_distance: Some(25),
+        }),
+        error_budget: Some(ErrorBudget::Split { logical: 0.001, t_states: 0.002, rotations: 0.0 }),
+        constraints: Some(EstimationConstraints { max_t_factories: Some(4), max_duration_ns: Some(1_000_000), ..Default::default() }),
+        distillation_unit_specifications: Some(vec![DistillationUnitSpecification {
+            name: Some("15-1 RM".to_string()),
+            ..Default::default()
+        }]),
+    };
+
+    let json: Value = from_str(&estimator_params_json(vec![params]).unwrap()).unwrap();
+
+    assert_eq!(json[0]["estimateType"], "frontier");
+    assert_eq!(json[0]["qubitParams"]["name"], "qubit_maj_ns_e6");
+    assert_eq!(json[0]["qecScheme"]["name"], "floquet_code");
+    assert_eq!(json[0]["qecScheme"]["maxCodeDistance"], 25);
+    assert_eq!(json[0]["errorBudget"]["tstates"], 0.002);
+    assert_eq!(json[0]["constraints"]["maxTFactories"], 4);
+    assert_eq!(json[0]["constraints"]["maxDuration"], "1000000 ns");
+    assert_eq!(json[0]["distillationUnitSpecifications"][0]["name"], "15-1 RM");
+}
+
+#[test]
+fn estimator_params_json_serializes_custom_qubits() {
+    let params = EstimatorParams {
+        qubit_model: Some(QubitModel::Custom { params: custom_gate_based_qubit() }),
+        error_budget: Some(ErrorBudget::Total { budget: 0.01 }),
+        ..Default::default()
+    };
+
+    let json: Value = from_str(&estimator_params_json(vec![params]).unwrap()).unwrap();
+
+    assert_eq!(json[0]["qubitParams"]["instructionSet"], "GateBased");
+    assert_eq!(json[0]["qubitParams"]["oneQubitGateTime"], "50 ns");
+    assert_eq!(json[0]["qubitParams"]["twoQubitGateErrorRate"], 1e-3);
+    assert!(json[0]["qubitParams"].get("idleErrorRate").is_none());
+    assert_eq!(json[0]["errorBudget"], 0.01);
+}
+
+#[test]
+fn estimator_params_json_defaults_to_a_single_configuration() {
+    assert_eq!(estimator_params_json(vec![]).unwrap(), "[{}]");
+    assert_eq!(estimator_params_json(vec![EstimatorParams::default()]).unwrap(), "[{}]");
+}
+
+#[test]
+fn estimator_params_reject_invalid_configurations() {
+    let mut qubit = custom_gate_based_qubit();
+    qubit.two_qubit_gate_error_rate = None;
+    let missing_rate = EstimatorParams { qubit_model: Some(QubitModel::Custom { params: qubit }), ..Default::default() };
+    assert_eq!(parameter_of(missing_rate.validate().unwrap_err()), "qubit_model.two_qubit_gate_error_rate");
+
+    let floquet_on_gates = EstimatorParams {
+        qubit_model: Some(QubitModel::Predefined { model: PredefinedQubitModel::GateNsE3 }),
+        qec_scheme: Some(QecScheme {
+            name: QecSchemeName::FloquetCode,
+            error_correction_threshold: None,
+            crossing_prefactor: None,
+            logical_cycle_time: None,
+            physical_qubits_per_logical_qubit: None,
+            max_code_distance: None,
+        }),
+        ..Default::default()
+    };
+    assert_eq!(parameter_of(floquet_on_gates.validate().unwrap_err()), "qec_scheme.name");
+
+    let budget = EstimatorParams { error_budget: Some(ErrorBudget::Total { budget: 1.5 }), ..Default::default() };
+    assert_eq!(parameter_of(estimator_params_json(vec![budget]).unwrap_err()), "error_budget.budget");
+
+    let conflicting = EstimatorParams {
+        constraints: Some(EstimationConstraints { max_duration_ns: Some(1000), max_physical_qubits: Some(1000), ..Default::default() }),
+        ..Default::default()
+    };
+    assert_eq!(parameter_of(conflicting.validate().unwrap_err()), "constraints");
+
+    let incomplete_unit = EstimatorParams {
+        distillation_unit_specifications: Some(vec![DistillationUnitSpecification { num_input_ts: Some(15), ..Default::default() }]),
+        ..Default::default()
+    };
+    assert_eq!(parameter_of(incomplete_unit.validate().unwrap_err()), "distillation_unit_specifications.num_output_ts");
+}
+
+#[test]
+fn estimate_with_typed_params() {
+    let source = std::fs::read_to_string("tests/assets/entanglement.qs").unwrap();
+    let params = EstimatorParams {
+        qubit_model: Some(QubitModel::Predefined { model: PredefinedQubitModel::GateUsE4 }),
+        error_budget: Some(ErrorBudget::Total { budget: 0.005 }),
+        ..Default::default()
+    };
+
+    let result = estimate_with_params(&source, vec![params]).unwrap();
+
+    let v: Value = from_str(&result).unwrap();
+    assert_eq!(v[0]["status"].as_str(), Some("success"));
+    assert_eq!(v[0]["jobParams"]["qubitParams"]["name"], "qubit_gate_us_e4");
+}
+
+#[test]
+fn estimate_results_are_typed() {
+    let source = std::fs::read_to_string("tests/assets/entanglement.qs").unwrap();
+
+    let results = estimate_results(&source, vec![]).unwrap();
+
+    assert_eq!(results.len(), 1);
+    let result = &results[0];
+    assert_eq!(result.qubit_model, "qubit_gate_ns_e3");
+    assert_eq!(result.qec_scheme, "surface_code");
+    assert!(result.physical_counts.physical_qubits > 0);
+    assert!(result.logical_counts.num_qubits > 0);
+    assert!(result.logical_qubit.code_distance > 0);
+    assert!(!result.report_groups.is_empty());
+}
+
+#[test]
+fn parse_estimation_results_reads_the_estimator_output() {
+    let json = SAMPLE_RESULT;
+
+    let results = parse_estimation_results(json).unwrap();
+
+    let result = &results[0];
+    assert_eq!(result.qubit_model, "qubit_gate_ns_e3");
+    assert_eq!(result.qec_scheme, "surface_code");
+    assert_eq!(result.physical_counts.runtime_ns, 5000);
+    assert_eq!(result.physical_counts.breakdown.num_t_factories, 2);
+    assert_eq!(result.physical_counts.breakdown.num_ts_per_rotation, None);
+    assert_eq!(result.logical_counts.t_count, 4);
+    assert_eq!(result.logical_qubit.logical_cycle_time_ns, 500);
+    let t_factory = result.t_factory.as_ref().expect("should have a T factory");
+    assert_eq!(t_factory.unit_name_per_round, vec!["15-to-1 space-efficient".to_string()]);
+    assert_eq!(result.error_budget.t_states, 0.0005);
+    assert_eq!(result.report_groups[0].entries[0].value, "5 microsecs");
+    assert_eq!(result.report_groups[0].entries[1].value, "1200");
+    assert_eq!(result.assumptions, vec!["Uniform independent physical noise.".to_string()]);
+}
+
+#[test]
+fn parse_estimation_results_reads_frontier_entries() {
+    // a frontier result keeps the counts of each point in its entries
+    let mut result: Value = from_str(SAMPLE_RESULT).unwrap();
+    let result = result.as_object_mut().unwrap();
+    let mut entry = serde_json::Map::new();
+    for key in ["physicalCounts", "logicalQubit", "tfactory", "errorBudget"] {
+        entry.insert(key.to_string(), result.remove(key).unwrap());
+    }
+    let mut slower = entry.clone();
+    slower["physicalCounts"]["runtime"] = 9000.into();
+    slower["physicalCounts"]["physicalQubits"] = 600.into();
+    slower["tfactory"] = Value::Null;
+    result.insert("frontierEntries".to_string(), vec![Value::Object(entry), Value::Object(slower)].into());
+
+    let results = parse_estimation_results(&Value::Object(result.clone()).to_string()).unwrap();
+
+    let result = &results[0];
+    assert_eq!(result.frontier.len(), 2);
+    assert_eq!(result.frontier[0].physical_counts, result.physical_counts);
+    assert_eq!(result.frontier[0].t_factory, result.t_factory);
+    assert_eq!(result.frontier[1].physical_counts.runtime_ns, 9000);
+    assert_eq!(result.frontier[1].physical_counts.physical_qubits, 600);
+    assert_eq!(result.frontier[1].t_factory, None);
+    assert_eq!(result.report_groups[0].entries[1].value, "1200");
+}
+
+#[test]
+fn parse_estimation_results_leaves_single_points_without_frontier() {
+    let results = parse_estimation_results(SAMPLE_RESULT).unwrap();
+    assert!(results[0].frontier.is_empty());
+}
+
+#[test]
+fn parse_estimation_results_rejects_incomplete_output() {
+    let error = parse_estimation_results(r#"[{ "status": "success" }]"#).unwrap_err();
+    assert!(error.to_string().contains("jobParams/qubitParams/name"));
+}
+
+fn sample_result(qubit_model: &str, runtime_ns: u64, physical_qubits: u64) -> EstimationResult {
+    let mut result = parse_estimation_results(SAMPLE_RESULT).unwrap().remove(0);
+    result.qubit_model = qubit_model.to_string();
+    result.physical_counts.runtime_ns = runtime_ns;
+    result.physical_counts.physical_qubits = physical_qubits;
+    result.physical_counts.breakdown.physical_qubits_for_t_factories = physical_qubits / 4;
+    result
+}
+
+#[test]
+fn pareto_frontier_keeps_non_dominated_results() {
+    let results = vec![
+        sample_result("slow_small", 9000, 100),
+        sample_result("fast_large", 1000, 900),
+        sample_result("dominated", 9500, 950),
+        sample_result("balanced", 4000, 400),
+    ];
+
+    let frontier = pareto_frontier(results);
+
+    let names: Vec<&str> = frontier.iter().map(|r| r.qubit_model.as_str()).collect();
+    assert_eq!(names, vec!["fast_large", "balanced", "slow_small"]);
+}
+
+#[test]
+fn comparison_table_summarizes_results() {
+    let results = vec![sample_result("slow_small", 9000, 100), sample_result("dominated", 9500, 200)];
+
+    let table = comparison_table(results);
+
+    assert_eq!(table.len(), 2);
+    assert_eq!(table[0].name, "slow_small (surface_code)");
+    assert_eq!(table[0].runtime_ns, 9000);
+    assert_eq!(table[0].physical_qubits, 100);
+    assert_eq!(table[0].rqops, 4000000);
+    assert_eq!(table[0].t_factory_fraction, 0.25);
+    assert!(table[0].is_pareto_optimal);
+    assert!(!table[1].is_pareto_optimal);
+}
+
+#[test]
+fn estimate_frontier_trades_runtime_for_qubits() {
+    let source = std::fs::read_to_string("tests/assets/entanglement.qs").unwrap();
+
+    let frontier = estimate_frontier(&source, None).unwrap();
+
+    assert!(!frontier.is_empty());
+    for points in frontier.windows(2) {
+        assert!(points[0].physical_counts.runtime_ns <= points[1].physical_counts.runtime_ns);
+        assert!(points[0].physical_counts.physical_qubits >= points[1].physical_counts.physical_qubits);
+    }
+}
+
+#[test]
+fn estimate_batch_uses_the_predefined_qubit_models() {
+    let source = std::fs::read_to_string("tests/assets/entanglement.qs").unwrap();
+
+    let results = estimate_batch(&source, None).unwrap();
+
+    assert_eq!(results.len(), predefined_estimator_params().len());
+    let models: Vec<&str> = results.iter().map(|r| r.qubit_model.as_str()).collect();
+    assert_eq!(
+        models,
+        vec!["qubit_gate_ns_e3", "qubit_gate_ns_e4", "qubit_gate_us_e3", "qubit_gate_us_e4", "qubit_maj_ns_e4", "qubit_maj_ns_e6"]
+    );
+    assert!(!pareto_frontier(results).is_empty());
+}
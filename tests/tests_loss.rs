@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+
+use qsharp_bridge::loss::loss_statistics;
+use qsharp_bridge::sim::{ExecutionState, MeasurementOutcome};
+
+fn shot(lost_qubits: Vec<u64>, outcomes: Vec<MeasurementOutcome>) -> ExecutionState {
+    ExecutionState {
+        lost_qubits,
+        outcomes,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_loss_statistics() {
+    use MeasurementOutcome::{Loss, One, Zero};
+
+    let results = vec![
+        shot(vec![], vec![Zero, One]),
+        shot(vec![1], vec![Zero, Loss]),
+        shot(vec![0, 1], vec![Loss, Loss]),
+        shot(vec![], vec![One, One]),
+    ];
+    let statistics = loss_statistics(results);
+
+    assert_eq!(statistics.shots, 4);
+    assert_eq!(statistics.shots_with_loss, 2);
+    assert_eq!(statistics.loss_rate, 0.5);
+    assert_eq!(statistics.qubit_loss_counts, HashMap::from([(0, 1), (1, 2)]));
+    assert_eq!(statistics.lost_qubits_histogram, HashMap::from([(0, 2), (1, 1), (2, 1)]));
+    assert_eq!(statistics.measurement_loss_rate, 3.0 / 8.0);
+}
+
+#[test]
+fn test_loss_statistics_without_shots() {
+    let statistics = loss_statistics(vec![]);
+
+    assert_eq!(statistics.shots, 0);
+    assert_eq!(statistics.loss_rate, 0.0);
+    assert_eq!(statistics.measurement_loss_rate, 0.0);
+    assert!(statistics.lost_qubits_histogram.is_empty());
+}
@@ -1,4 +1,6 @@
-use qsharp_bridge::noise::{Noise, PauliNoiseDistribution};
+use std::collections::HashMap;
+
+use qsharp_bridge::noise::{KrausOperator, Noise, NoiseModel, PauliNoiseDistribution, TwoQubitNoise};
 
 #[test]
 fn test_ideal_noise_to_distribution() {
@@ -65,3 +67,85 @@ fn test_pauli_distribution_new_invalid_sum() {
     let dist = PauliNoiseDistribution::new(0.5, 0.6, 0.1);
     assert!(dist.is_err());
 }
+
+#[test]
+fn test_noise_model_validate_valid() {
+    let model = NoiseModel {
+        h: Some(Noise::Depolarizing { p: 0.01 }),
+        cx: Some(TwoQubitNoise::Correlated {
+            probabilities: HashMap::from([("XX".to_string(), 0.01), ("IZ".to_string(), 0.02)]),
+        }),
+        cz: Some(TwoQubitNoise::Independent {
+            noise: Noise::BitFlip { p: 0.1 },
+        }),
+        ..Default::default()
+    };
+    assert!(model.validate().is_ok());
+}
+
+#[test]
+fn test_noise_model_validate_invalid_gate_noise() {
+    let model = NoiseModel {
+        mz: Some(Noise::BitFlip { p: 1.5 }),
+        ..Default::default()
+    };
+    assert!(model.validate().is_err());
+}
+
+#[test]
+fn test_noise_model_validate_invalid_correlated_noise() {
+    for probabilities in [
+        HashMap::from([("XQ".to_string(), 0.1)]),
+        HashMap::from([("II".to_string(), 0.1)]),
+        HashMap::from([("XYZ".to_string(), 0.1)]),
+        HashMap::from([("XX".to_string(), 0.6), ("ZZ".to_string(), 0.6)]),
+    ] {
+        let model = NoiseModel {
+            cx: Some(TwoQubitNoise::Correlated { probabilities }),
+            ..Default::default()
+        };
+        assert!(model.validate().is_err());
+    }
+}
+
+#[test]
+fn test_non_pauli_noise_to_distribution() {
+    let noise = Noise::AmplitudeDamping { gamma: 0.1 };
+    assert!(noise.to_distribution().is_err());
+}
+
+#[test]
+fn test_noise_model_validate_non_pauli_noise() {
+    let valid = NoiseModel {
+        h: Some(Noise::AmplitudeDamping { gamma: 0.1 }),
+        x: Some(Noise::PhaseDamping { lambda: 0.2 }),
+        cx: Some(TwoQubitNoise::Independent {
+            noise: Noise::ThermalRelaxation { t1: 100.0, t2: 80.0, gate_time: 1.0 },
+        }),
+        rz: Some(Noise::Kraus {
+            operators: vec![
+                KrausOperator { real: vec![1.0, 0.0, 0.0, 0.6], imaginary: vec![0.0; 4] },
+                KrausOperator { real: vec![0.0, 0.8, 0.0, 0.0], imaginary: vec![0.0; 4] },
+            ],
+        }),
+        ..Default::default()
+    };
+    assert!(valid.validate().is_ok());
+
+    for noise in [
+        Noise::AmplitudeDamping { gamma: 1.5 },
+        Noise::ThermalRelaxation { t1: 100.0, t2: 250.0, gate_time: 1.0 },
+        Noise::Kraus {
+            operators: vec![KrausOperator { real: vec![1.0, 0.0, 0.0, 0.5], imaginary: vec![0.0; 4] }],
+        },
+        Noise::Kraus {
+            operators: vec![KrausOperator { real: vec![1.0, 0.0], imaginary: vec![0.0, 0.0] }],
+        },
+    ] {
+        let model = NoiseModel {
+            h: Some(noise),
+            ..Default::default()
+        };
+        assert!(model.validate().is_err());
+    }
+}
@@ -0,0 +1,74 @@
+use qsharp_bridge::estimation::{InstructionSet, PredefinedQubitModel, QubitModel};
+use qsharp_bridge::noise::{Noise, PauliNoiseDistribution};
+use qsharp_bridge::noise_estimation::{noise_from_qubit_model, qubit_params_from_noise};
+use qsharp_bridge::sim::QsError;
+
+fn predefined(model: PredefinedQubitModel) -> QubitModel {
+    QubitModel::Predefined { model }
+}
+
+#[test]
+fn test_qubit_params_from_noise() {
+    let noise = Noise::Pauli {
+        noise: PauliNoiseDistribution::new(0.01, 0.02, 0.03).unwrap(),
+    };
+    let params = qubit_params_from_noise(noise, predefined(PredefinedQubitModel::GateNsE3)).unwrap();
+
+    assert_eq!(params.name, "qubit_gate_ns_e3_noise");
+    assert_eq!(params.instruction_set, InstructionSet::GateBased);
+    assert!((params.one_qubit_gate_error_rate.unwrap() - 0.06).abs() < 1e-12);
+    assert!((params.two_qubit_gate_error_rate.unwrap() - (1.0 - 0.94 * 0.94)).abs() < 1e-12);
+    assert!((params.one_qubit_measurement_error_rate - 0.03).abs() < 1e-12);
+    assert!((params.t_gate_error_rate - 0.06).abs() < 1e-12);
+    // times and idle errors come from the base model
+    assert_eq!(params.one_qubit_gate_time_ns, Some(50));
+    assert_eq!(params.one_qubit_measurement_time_ns, 100);
+    assert_eq!(params.idle_error_rate, Some(1e-3));
+}
+
+#[test]
+fn test_qubit_params_from_noise_rejects_majorana_base() {
+    let result = qubit_params_from_noise(Noise::Depolarizing { p: 0.01 }, predefined(PredefinedQubitModel::MajNsE4));
+    assert!(matches!(result, Err(QsError::InvalidParameter { parameter, .. }) if parameter == "base"));
+}
+
+#[test]
+fn test_qubit_params_from_noise_rejects_non_pauli_noise() {
+    let noise = Noise::AmplitudeDamping { gamma: 0.1 };
+    assert!(qubit_params_from_noise(noise, predefined(PredefinedQubitModel::GateNsE4)).is_err());
+}
+
+#[test]
+fn test_noise_from_qubit_model() {
+    let noise = noise_from_qubit_model(predefined(PredefinedQubitModel::GateUsE4)).unwrap();
+    assert!(matches!(noise, Noise::Depolarizing { p } if p == 1e-4));
+
+    let noise = noise_from_qubit_model(predefined(PredefinedQubitModel::MajNsE6)).unwrap();
+    assert!(matches!(noise, Noise::Depolarizing { p } if (p - 1.5e-6).abs() < 1e-18));
+}
+
+#[test]
+fn test_noise_round_trip_keeps_gate_error_rate() {
+    for model in PredefinedQubitModel::ALL {
+        if model.instruction_set() != InstructionSet::GateBased {
+            continue;
+        }
+        let noise = noise_from_qubit_model(predefined(model)).unwrap();
+        let params = qubit_params_from_noise(noise, predefined(model)).unwrap();
+        assert_eq!(params.one_qubit_gate_error_rate, model.params().one_qubit_gate_error_rate);
+    }
+}
+
+#[test]
+fn test_noise_round_trip_keeps_majorana_measurement_error_rate() {
+    for model in PredefinedQubitModel::ALL {
+        if model.instruction_set() == InstructionSet::GateBased {
+            continue;
+        }
+        let noise = noise_from_qubit_model(predefined(model)).unwrap();
+        // Majorana models can't be a base, the measurement error rate comes from the noise alone
+        let params = qubit_params_from_noise(noise, predefined(PredefinedQubitModel::GateNsE3)).unwrap();
+        let expected = model.params().one_qubit_measurement_error_rate;
+        assert!((params.one_qubit_measurement_error_rate - expected).abs() < expected * 1e-12);
+    }
+}
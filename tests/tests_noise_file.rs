@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use expect_test::expect;
+use qsharp_bridge::noise::{DeviceNoiseModel, Noise, NoiseModel, TwoQubitNoise};
+use qsharp_bridge::noise_file::{
+    noise_from_json, noise_model_from_json, noise_model_from_toml, noise_model_to_json, noise_model_to_toml,
+    noise_to_json,
+};
+use qsharp_bridge::readout::ReadoutError;
+use qsharp_bridge::sim::QsError;
+
+const CALIBRATION_JSON: &str = r#"{
+    "noise": { "type": "depolarizing", "p": 0.001 },
+    "gates": {
+        "h": { "type": "pauli", "x": 0.01, "y": 0.0, "z": 0.02 },
+        "cx": { "type": "correlated", "probabilities": { "XX": 0.01, "ZZ": 0.02 } },
+        "mz": { "type": "bit_flip", "p": 0.005 }
+    },
+    "readout_error": { "p_one_given_zero": 0.01, "p_zero_given_one": 0.03 },
+    "qubit_readout_errors": { "2": { "p_one_given_zero": 0.1, "p_zero_given_one": 0.2 } },
+    "qubit_loss": 0.002,
+    "qubit_noise": { "5": { "type": "depolarizing", "p": 0.05 } },
+    "qubit_losses": { "5": 0.1 },
+    "idle_noise": { "noise": { "type": "phase_flip", "p": 0.001 }, "loss": 0.0001 }
+}"#;
+
+fn invalid_parameter(error: QsError) -> (String, String) {
+    match error {
+        QsError::InvalidParameter { parameter, reason } => (parameter, reason),
+        other => panic!("unexpected error: {other}"),
+    }
+}
+
+#[test]
+fn test_noise_model_from_json() {
+    let model = noise_model_from_json(CALIBRATION_JSON).unwrap();
+
+    assert!(matches!(model.noise, Noise::Depolarizing { p } if p == 0.001));
+    let gates = model.gates.unwrap();
+    let Some(Noise::Pauli { noise }) = gates.h else {
+        panic!("expected a Pauli channel on h");
+    };
+    assert_eq!((noise.x, noise.y, noise.z), (0.01, 0.0, 0.02));
+    let Some(TwoQubitNoise::Correlated { probabilities }) = gates.cx else {
+        panic!("expected a correlated channel on cx");
+    };
+    assert_eq!(probabilities["ZZ"], 0.02);
+    assert!(matches!(gates.mz, Some(Noise::BitFlip { p }) if p == 0.005));
+    assert!(gates.x.is_none());
+
+    assert_eq!(model.readout_error, Some(ReadoutError::new(0.01, 0.03).unwrap()));
+    assert_eq!(
+        model.qubit_readout_errors.unwrap()[&2],
+        ReadoutError::new(0.1, 0.2).unwrap()
+    );
+    assert_eq!(model.qubit_loss, Some(0.002));
+    assert!(matches!(model.qubit_noise.unwrap()[&5], Noise::Depolarizing { p } if p == 0.05));
+    assert_eq!(model.qubit_losses.unwrap()[&5], 0.1);
+    let idle_noise = model.idle_noise.unwrap();
+    assert!(matches!(idle_noise.noise, Noise::PhaseFlip { p } if p == 0.001));
+    assert_eq!(idle_noise.loss, Some(0.0001));
+}
+
+#[test]
+fn test_noise_model_json_round_trip() {
+    let model = noise_model_from_json(CALIBRATION_JSON).unwrap();
+    let json = noise_model_to_json(model);
+    let again = noise_model_to_json(noise_model_from_json(&json).unwrap());
+    assert_eq!(json, again);
+}
+
+#[test]
+fn test_noise_model_to_toml() {
+    let model = DeviceNoiseModel {
+        noise: Noise::BitFlip { p: 0.01 },
+        gates: Some(NoiseModel {
+            cz: Some(TwoQubitNoise::Correlated {
+                probabilities: HashMap::from([("ZZ".to_string(), 0.05)]),
+            }),
+            ..Default::default()
+        }),
+        qubit_readout_errors: Some(HashMap::from([(1, ReadoutError::new(0.0, 0.25).unwrap())])),
+        qubit_loss: Some(0.5),
+        ..Default::default()
+    };
+
+    let toml = noise_model_to_toml(model).unwrap();
+    expect![[r#"
+        qubit_loss = 0.5
+        [gates.cz]
+        type = "correlated"
+
+        [gates.cz.probabilities]
+        ZZ = 0.05
+
+        [noise]
+        p = 0.01
+        type = "bit_flip"
+        [qubit_readout_errors.1]
+        p_one_given_zero = 0.0
+        p_zero_given_one = 0.25
+    "#]]
+    .assert_eq(&toml);
+
+    let model = noise_model_from_toml(&toml).unwrap();
+    assert!(matches!(model.noise, Noise::BitFlip { p } if p == 0.01));
+    assert!(model.gates.unwrap().cz.is_some());
+    assert_eq!(model.qubit_loss, Some(0.5));
+}
+
+#[test]
+fn test_noise_json_round_trip() {
+    let noise = Noise::ThermalRelaxation {
+        t1: 100.0,
+        t2: 80.0,
+        gate_time: 0.05,
+    };
+    let json = noise_to_json(noise);
+    expect![[r#"
+        {
+          "gate_time": 0.05,
+          "t1": 100.0,
+          "t2": 80.0,
+          "type": "thermal_relaxation"
+        }"#]]
+    .assert_eq(&json);
+    assert!(matches!(
+        noise_from_json(&json).unwrap(),
+        Noise::ThermalRelaxation { t1, t2, gate_time } if t1 == 100.0 && t2 == 80.0 && gate_time == 0.05
+    ));
+}
+
+#[test]
+fn test_noise_from_json_invalid_pauli_distribution() {
+    let error = noise_from_json(r#"{ "type": "pauli", "x": 0.5, "y": 0.4, "z": 0.3 }"#).unwrap_err();
+    let (parameter, reason) = invalid_parameter(error);
+    assert_eq!(parameter, "noise");
+    assert_eq!(reason, "Invalid Pauli distribution: values must be non-negative and sum to <= 1.0");
+}
+
+#[test]
+fn test_noise_model_from_json_reports_path_of_invalid_entry() {
+    let error = noise_model_from_json(r#"{ "gates": { "cx": { "type": "depolarizing", "p": 1.5 } } }"#).unwrap_err();
+    assert_eq!(invalid_parameter(error).0, "gates.cx.p");
+
+    let error =
+        noise_model_from_json(r#"{ "gates": { "cx": { "type": "correlated", "probabilities": { "II": 0.1 } } } }"#)
+            .unwrap_err();
+    assert_eq!(invalid_parameter(error).0, "gates.cx");
+
+    let error = noise_model_from_json(r#"{ "qubit_readout_errors": { "a": {} } }"#).unwrap_err();
+    assert_eq!(invalid_parameter(error).0, "qubit_readout_errors");
+
+    let error = noise_model_from_json(r#"{ "qubit_losses": { "3": 2.0 } }"#).unwrap_err();
+    assert_eq!(invalid_parameter(error).0, "qubit_losses.3");
+
+    let error = noise_model_from_json(r#"{ "idle_noise": { "noise": { "type": "amplitude_damping", "gamma": 0.1 } } }"#)
+        .unwrap_err();
+    assert_eq!(invalid_parameter(error).0, "idle_noise.noise");
+}
+
+#[test]
+fn test_noise_model_from_json_rejects_unknown_keys() {
+    let error = noise_model_from_json(r#"{ "gates": { "cnot": { "type": "ideal" } } }"#).unwrap_err();
+    let (parameter, reason) = invalid_parameter(error);
+    assert_eq!(parameter, "gates");
+    assert_eq!(reason, "unknown key `cnot`");
+
+    let error = noise_from_json(r#"{ "type": "depolarising", "p": 0.1 }"#).unwrap_err();
+    assert_eq!(invalid_parameter(error).0, "noise.type");
+}
+
+#[test]
+fn test_noise_model_from_invalid_text() {
+    assert!(matches!(noise_model_from_json("{"), Err(QsError::ErrorMessage { .. })));
+    assert!(matches!(noise_model_from_toml("noise = "), Err(QsError::ErrorMessage { .. })));
+}
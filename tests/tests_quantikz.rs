@@ -1,5 +1,7 @@
 use expect_test::expect;
 use qsharp_bridge::quantikz::{quantikz, quantikz_operation, QuantikzGenerationOptions};
+use qsharp_bridge::circuit::{CircuitGenerationMethod, CircuitGenerationOptions};
+use std::collections::HashMap;
 
 #[test]
 fn quantikz_one_gate() {
@@ -14,12 +16,13 @@ fn quantikz_one_gate() {
                 }
             }
         ",
-        QuantikzGenerationOptions { group_by_scope: false }
+        QuantikzGenerationOptions::default()
     ).expect("quantikz generation should succeed");
 
     expect![[r#"
         \begin{quantikz}
-        \lstick{$\ket{0}_{0}$} & \gate{H} & \meter{} & \cw \\
+        \lstick{$\ket{0}_{0}$} & \gate{H} & \meter{} \vcw{1} & \qw \\
+        \lstick{$c_{0,0}$}\setwiretype{n} &  & \setwiretype{c} & \cw \\
         \end{quantikz}
     "#]]
     .assert_eq(&tex);
@@ -36,10 +39,11 @@ fn quantikz_operation_one_gate() {
             }
         }
     ";
-    let tex = quantikz_operation("Test.Main", source, QuantikzGenerationOptions { group_by_scope: false }).expect("quantikz generation should succeed");
+    let tex = quantikz_operation("Test.Main", source, QuantikzGenerationOptions::default()).expect("quantikz generation should succeed");
     expect![[r#"
         \begin{quantikz}
-        \lstick{$\ket{0}_{0}$} & \gate{H} & \meter{} & \cw \\
+        \lstick{$\ket{0}_{0}$} & \gate{H} & \meter{} \vcw{1} & \qw \\
+        \lstick{$c_{0,0}$}\setwiretype{n} &  & \setwiretype{c} & \cw \\
         \end{quantikz}
     "#]]
     .assert_eq(&tex);
@@ -57,7 +61,7 @@ fn quantikz_toffoli() {
                 }
             }
         ",
-        QuantikzGenerationOptions { group_by_scope: false }
+        QuantikzGenerationOptions::default()
     ).expect("quantikz generation should succeed");
 
     expect![[r#"
@@ -82,7 +86,7 @@ fn quantikz_operation_toffoli() {
             }
         ";
 
-    let tex = quantikz_operation("Test.Main", source, QuantikzGenerationOptions { group_by_scope: false }).expect("quantikz generation should succeed");
+    let tex = quantikz_operation("Test.Main", source, QuantikzGenerationOptions::default()).expect("quantikz generation should succeed");
 
     expect![[r#"
         \begin{quantikz}
@@ -106,7 +110,7 @@ fn quantikz_swap_gate() {
                 }
             }
         ",
-        QuantikzGenerationOptions { group_by_scope: false }
+        QuantikzGenerationOptions::default()
     ).expect("quantikz generation should succeed");
 
     expect![[r#"
@@ -130,7 +134,7 @@ fn quantikz_operation_swap_gate() {
             }
         ";
 
-    let tex = quantikz_operation("Test.Main", source, QuantikzGenerationOptions { group_by_scope: false }).expect("quantikz generation should succeed");
+    let tex = quantikz_operation("Test.Main", source, QuantikzGenerationOptions::default()).expect("quantikz generation should succeed");
 
     expect![[r#"
         \begin{quantikz}
@@ -162,14 +166,17 @@ fn quantikz_complex_sample() {
                 }
             }
         "#,
-        QuantikzGenerationOptions { group_by_scope: false }
+        QuantikzGenerationOptions::default()
     ).expect("quantikz generation should succeed");
 
     expect![[r#"
         \begin{quantikz}
-        \lstick{$\ket{0}_{0}$} & \gate{R_z(0.3927)} & \qw & \qw & \qw & \qw & \qw & \ctrl{2} & \qw & \ctrl{2} & \gate{T} & \qw & \ctrl{1} & \qw & \ctrl{1} & \gate{H} & \swap{2} & \meter{} & \cw \\
-        \lstick{$\ket{0}_{1}$} & \gate{T} & \qw & \ctrl{1} & \qw & \ctrl{1} & \qw & \qw & \qw & \qw & \gate{H} & \gate{T} & \targ{} & \gate{T^\dagger} & \targ{} & \qw & \qw & \meter{} & \cw \\
-        \lstick{$\ket{0}_{2}$} & \gate{H} & \gate{T} & \targ{} & \gate{T^\dagger} & \targ{} & \gate{R_z(0.3927)} & \targ{} & \gate{R_z(-0.3927)} & \targ{} & \qw & \qw & \qw & \qw & \qw & \qw & \targX{} & \meter{} & \cw \\
+        \lstick{$\ket{0}_{0}$} & \gate{R_z(0.3927)} & \qw & \qw & \qw & \qw & \qw & \ctrl{4} & \qw & \ctrl{4} & \gate{T} & \qw & \ctrl{2} & \qw & \ctrl{2} & \gate{H} & \swap{4} & \meter{} \vcw{1} & \qw \\
+        \lstick{$c_{0,0}$}\setwiretype{n} &  &  &  &  &  &  &  &  &  &  &  &  &  &  &  &  & \setwiretype{c} & \cw \\
+        \lstick{$\ket{0}_{1}$} & \gate{T} & \qw & \ctrl{2} & \qw & \ctrl{2} & \qw & \qw & \qw & \qw & \gate{H} & \gate{T} & \targ{} & \gate{T^\dagger} & \targ{} & \qw & \qw & \meter{} \vcw{1} & \qw \\
+        \lstick{$c_{1,0}$}\setwiretype{n} &  &  &  &  &  &  &  &  &  &  &  &  &  &  &  &  & \setwiretype{c} & \cw \\
+        \lstick{$\ket{0}_{2}$} & \gate{H} & \gate{T} & \targ{} & \gate{T^\dagger} & \targ{} & \gate{R_z(0.3927)} & \targ{} & \gate{R_z(-0.3927)} & \targ{} & \qw & \qw & \qw & \qw & \qw & \qw & \targX{} & \meter{} \vcw{1} & \qw \\
+        \lstick{$c_{2,0}$}\setwiretype{n} &  &  &  &  &  &  &  &  &  &  &  &  &  &  &  &  & \setwiretype{c} & \cw \\
         \end{quantikz}
     "#]].assert_eq(&tex);
 }
@@ -194,13 +201,16 @@ fn quantikz_operation_complex_sample() {
         }
     "#;
 
-    let tex = quantikz_operation("Test.Main", source, QuantikzGenerationOptions { group_by_scope: false }).expect("quantikz generation should succeed");
+    let tex = quantikz_operation("Test.Main", source, QuantikzGenerationOptions::default()).expect("quantikz generation should succeed");
 
     expect![[r#"
         \begin{quantikz}
-        \lstick{$\ket{0}_{0}$} & \gate{R_z(0.3927)} & \qw & \qw & \qw & \qw & \qw & \ctrl{2} & \qw & \ctrl{2} & \gate{T} & \qw & \ctrl{1} & \qw & \ctrl{1} & \gate{H} & \swap{2} & \meter{} & \cw \\
-        \lstick{$\ket{0}_{1}$} & \gate{T} & \qw & \ctrl{1} & \qw & \ctrl{1} & \qw & \qw & \qw & \qw & \gate{H} & \gate{T} & \targ{} & \gate{T^\dagger} & \targ{} & \qw & \qw & \meter{} & \cw \\
-        \lstick{$\ket{0}_{2}$} & \gate{H} & \gate{T} & \targ{} & \gate{T^\dagger} & \targ{} & \gate{R_z(0.3927)} & \targ{} & \gate{R_z(-0.3927)} & \targ{} & \qw & \qw & \qw & \qw & \qw & \qw & \targX{} & \meter{} & \cw \\
+        \lstick{$\ket{0}_{0}$} & \gate{R_z(0.3927)} & \qw & \qw & \qw & \qw & \qw & \ctrl{4} & \qw & \ctrl{4} & \gate{T} & \qw & \ctrl{2} & \qw & \ctrl{2} & \gate{H} & \swap{4} & \meter{} \vcw{1} & \qw \\
+        \lstick{$c_{0,0}$}\setwiretype{n} &  &  &  &  &  &  &  &  &  &  &  &  &  &  &  &  & \setwiretype{c} & \cw \\
+        \lstick{$\ket{0}_{1}$} & \gate{T} & \qw & \ctrl{2} & \qw & \ctrl{2} & \qw & \qw & \qw & \qw & \gate{H} & \gate{T} & \targ{} & \gate{T^\dagger} & \targ{} & \qw & \qw & \meter{} \vcw{1} & \qw \\
+        \lstick{$c_{1,0}$}\setwiretype{n} &  &  &  &  &  &  &  &  &  &  &  &  &  &  &  &  & \setwiretype{c} & \cw \\
+        \lstick{$\ket{0}_{2}$} & \gate{H} & \gate{T} & \targ{} & \gate{T^\dagger} & \targ{} & \gate{R_z(0.3927)} & \targ{} & \gate{R_z(-0.3927)} & \targ{} & \qw & \qw & \qw & \qw & \qw & \qw & \targX{} & \meter{} \vcw{1} & \qw \\
+        \lstick{$c_{2,0}$}\setwiretype{n} &  &  &  &  &  &  &  &  &  &  &  &  &  &  &  &  & \setwiretype{c} & \cw \\
         \end{quantikz}
     "#]].assert_eq(&tex);
 }
@@ -226,13 +236,15 @@ fn quantikz_rotation_circuit() {
                 }
             }
         "#,
-        QuantikzGenerationOptions { group_by_scope: false }
+        QuantikzGenerationOptions::default()
     ).expect("quantikz generation should succeed");
 
     expect![[r#"
         \begin{quantikz}
-        \lstick{$\ket{0}_{0}$} & \gate{X} & \gate{H} & \ctrl{1} & \gate{H} & \meter{} & \cw & \cw \\
-        \lstick{$\ket{0}_{1}$} & \gate{X} & \qw & \targ{} & \gate{R_z(2.0944)} & \gate{H} & \meter{} & \cw \\
+        \lstick{$\ket{0}_{0}$} & \gate{X} & \gate{H} & \ctrl{2} & \gate{H} & \meter{} \vcw{1} & \qw & \qw \\
+        \lstick{$c_{0,0}$}\setwiretype{n} &  &  &  &  & \setwiretype{c} & \cw & \cw \\
+        \lstick{$\ket{0}_{1}$} & \gate{X} & \qw & \targ{} & \gate{R_z(2.0944)} & \gate{H} & \meter{} \vcw{1} & \qw \\
+        \lstick{$c_{1,0}$}\setwiretype{n} &  &  &  &  &  & \setwiretype{c} & \cw \\
         \end{quantikz}
     "#]]
     .assert_eq(&tex);
@@ -258,12 +270,14 @@ fn quantikz_operation_rotation_circuit() {
         }
     "#;
 
-    let tex = quantikz_operation("Test.Main", source, QuantikzGenerationOptions { group_by_scope: false }).expect("quantikz generation should succeed");
+    let tex = quantikz_operation("Test.Main", source, QuantikzGenerationOptions::default()).expect("quantikz generation should succeed");
 
     expect![[r#"
         \begin{quantikz}
-        \lstick{$\ket{0}_{0}$} & \gate{X} & \gate{H} & \ctrl{1} & \gate{H} & \meter{} & \cw & \cw \\
-        \lstick{$\ket{0}_{1}$} & \gate{X} & \qw & \targ{} & \gate{R_z(2.0944)} & \gate{H} & \meter{} & \cw \\
+        \lstick{$\ket{0}_{0}$} & \gate{X} & \gate{H} & \ctrl{2} & \gate{H} & \meter{} \vcw{1} & \qw & \qw \\
+        \lstick{$c_{0,0}$}\setwiretype{n} &  &  &  &  & \setwiretype{c} & \cw & \cw \\
+        \lstick{$\ket{0}_{1}$} & \gate{X} & \qw & \targ{} & \gate{R_z(2.0944)} & \gate{H} & \meter{} \vcw{1} & \qw \\
+        \lstick{$c_{1,0}$}\setwiretype{n} &  &  &  &  &  & \setwiretype{c} & \cw \\
         \end{quantikz}
     "#]]
     .assert_eq(&tex);
@@ -292,19 +306,27 @@ fn quantikz_cat_state() {
                 }
             }
         ",
-        QuantikzGenerationOptions { group_by_scope: false }
+        QuantikzGenerationOptions::default()
     ).expect("quantikz generation should succeed");
 
     expect![[r#"
         \begin{quantikz}
-        \lstick{$\ket{0}_{0}$} & \gate{H} & \ctrl{1} & \ctrl{2} & \ctrl{3} & \ctrl{4} & \ctrl{5} & \ctrl{6} & \ctrl{7} & \meter{} & \gate{\ket{0}} & \qw \\
-        \lstick{$\ket{0}_{1}$} & \qw & \targ{} & \qw & \qw & \qw & \qw & \qw & \qw & \meter{} & \gate{\ket{0}} & \qw \\
-        \lstick{$\ket{0}_{2}$} & \qw & \qw & \targ{} & \qw & \qw & \qw & \qw & \qw & \meter{} & \gate{\ket{0}} & \qw \\
-        \lstick{$\ket{0}_{3}$} & \qw & \qw & \qw & \targ{} & \qw & \qw & \qw & \qw & \meter{} & \gate{\ket{0}} & \qw \\
-        \lstick{$\ket{0}_{4}$} & \qw & \qw & \qw & \qw & \targ{} & \qw & \qw & \qw & \meter{} & \gate{\ket{0}} & \qw \\
-        \lstick{$\ket{0}_{5}$} & \qw & \qw & \qw & \qw & \qw & \targ{} & \qw & \qw & \meter{} & \gate{\ket{0}} & \qw \\
-        \lstick{$\ket{0}_{6}$} & \qw & \qw & \qw & \qw & \qw & \qw & \targ{} & \qw & \meter{} & \gate{\ket{0}} & \qw \\
-        \lstick{$\ket{0}_{7}$} & \qw & \qw & \qw & \qw & \qw & \qw & \qw & \targ{} & \meter{} & \gate{\ket{0}} & \qw \\
+        \lstick{$\ket{0}_{0}$} & \gate{H} & \ctrl{2} & \ctrl{4} & \ctrl{6} & \ctrl{8} & \ctrl{10} & \ctrl{12} & \ctrl{14} & \meter{} \vcw{1} & \gate{\ket{0}} & \qw \\
+        \lstick{$c_{0,0}$}\setwiretype{n} &  &  &  &  &  &  &  &  & \setwiretype{c} & \cw & \cw \\
+        \lstick{$\ket{0}_{1}$} & \qw & \targ{} & \qw & \qw & \qw & \qw & \qw & \qw & \meter{} \vcw{1} & \gate{\ket{0}} & \qw \\
+        \lstick{$c_{1,0}$}\setwiretype{n} &  &  &  &  &  &  &  &  & \setwiretype{c} & \cw & \cw \\
+        \lstick{$\ket{0}_{2}$} & \qw & \qw & \targ{} & \qw & \qw & \qw & \qw & \qw & \meter{} \vcw{1} & \gate{\ket{0}} & \qw \\
+        \lstick{$c_{2,0}$}\setwiretype{n} &  &  &  &  &  &  &  &  & \setwiretype{c} & \cw & \cw \\
+        \lstick{$\ket{0}_{3}$} & \qw & \qw & \qw & \targ{} & \qw & \qw & \qw & \qw & \meter{} \vcw{1} & \gate{\ket{0}} & \qw \\
+        \lstick{$c_{3,0}$}\setwiretype{n} &  &  &  &  &  &  &  &  & \setwiretype{c} & \cw & \cw \\
+        \lstick{$\ket{0}_{4}$} & \qw & \qw & \qw & \qw & \targ{} & \qw & \qw & \qw & \meter{} \vcw{1} & \gate{\ket{0}} & \qw \\
+        \lstick{$c_{4,0}$}\setwiretype{n} &  &  &  &  &  &  &  &  & \setwiretype{c} & \cw & \cw \\
+        \lstick{$\ket{0}_{5}$} & \qw & \qw & \qw & \qw & \qw & \targ{} & \qw & \qw & \meter{} \vcw{1} & \gate{\ket{0}} & \qw \\
+        \lstick{$c_{5,0}$}\setwiretype{n} &  &  &  &  &  &  &  &  & \setwiretype{c} & \cw & \cw \\
+        \lstick{$\ket{0}_{6}$} & \qw & \qw & \qw & \qw & \qw & \qw & \targ{} & \qw & \meter{} \vcw{1} & \gate{\ket{0}} & \qw \\
+        \lstick{$c_{6,0}$}\setwiretype{n} &  &  &  &  &  &  &  &  & \setwiretype{c} & \cw & \cw \\
+        \lstick{$\ket{0}_{7}$} & \qw & \qw & \qw & \qw & \qw & \qw & \qw & \targ{} & \meter{} \vcw{1} & \gate{\ket{0}} & \qw \\
+        \lstick{$c_{7,0}$}\setwiretype{n} &  &  &  &  &  &  &  &  & \setwiretype{c} & \cw & \cw \\
         \end{quantikz}
     "#]]
     .assert_eq(&tex);
@@ -332,18 +354,26 @@ fn quantikz_operation_cat_state() {
         }
     ";
 
-    let tex = quantikz_operation("Test.Main", source, QuantikzGenerationOptions { group_by_scope: false }).expect("quantikz generation should succeed");
+    let tex = quantikz_operation("Test.Main", source, QuantikzGenerationOptions::default()).expect("quantikz generation should succeed");
 
     expect![[r#"
         \begin{quantikz}
-        \lstick{$\ket{0}_{0}$} & \gate{H} & \ctrl{1} & \ctrl{2} & \ctrl{3} & \ctrl{4} & \ctrl{5} & \ctrl{6} & \ctrl{7} & \meter{} & \gate{\ket{0}} & \qw \\
-        \lstick{$\ket{0}_{1}$} & \qw & \targ{} & \qw & \qw & \qw & \qw & \qw & \qw & \meter{} & \gate{\ket{0}} & \qw \\
-        \lstick{$\ket{0}_{2}$} & \qw & \qw & \targ{} & \qw & \qw & \qw & \qw & \qw & \meter{} & \gate{\ket{0}} & \qw \\
-        \lstick{$\ket{0}_{3}$} & \qw & \qw & \qw & \targ{} & \qw & \qw & \qw & \qw & \meter{} & \gate{\ket{0}} & \qw \\
-        \lstick{$\ket{0}_{4}$} & \qw & \qw & \qw & \qw & \targ{} & \qw & \qw & \qw & \meter{} & \gate{\ket{0}} & \qw \\
-        \lstick{$\ket{0}_{5}$} & \qw & \qw & \qw & \qw & \qw & \targ{} & \qw & \qw & \meter{} & \gate{\ket{0}} & \qw \\
-        \lstick{$\ket{0}_{6}$} & \qw & \qw & \qw & \qw & \qw & \qw & \targ{} & \qw & \meter{} & \gate{\ket{0}} & \qw \\
-        \lstick{$\ket{0}_{7}$} & \qw & \qw & \qw & \qw & \qw & \qw & \qw & \targ{} & \meter{} & \gate{\ket{0}} & \qw \\
+        \lstick{$\ket{0}_{0}$} & \gate{H} & \ctrl{2} & \ctrl{4} & \ctrl{6} & \ctrl{8} & \ctrl{10} & \ctrl{12} & \ctrl{14} & \meter{} \vcw{1} & \gate{\ket{0}} & \qw \\
+        \lstick{$c_{0,0}$}\setwiretype{n} &  &  &  &  &  &  &  &  & \setwiretype{c} & \cw & \cw \\
+        \lstick{$\ket{0}_{1}$} & \qw & \targ{} & \qw & \qw & \qw & \qw & \qw & \qw & \meter{} \vcw{1} & \gate{\ket{0}} & \qw \\
+        \lstick{$c_{1,0}$}\setwiretype{n} &  &  &  &  &  &  &  &  & \setwiretype{c} & \cw & \cw \\
+        \lstick{$\ket{0}_{2}$} & \qw & \qw & \targ{} & \qw & \qw & \qw & \qw & \qw & \meter{} \vcw{1} & \gate{\ket{0}} & \qw \\
+        \lstick{$c_{2,0}$}\setwiretype{n} &  &  &  &  &  &  &  &  & \setwiretype{c} & \cw & \cw \\
+        \lstick{$\ket{0}_{3}$} & \qw & \qw & \qw & \targ{} & \qw & \qw & \qw & \qw & \meter{} \vcw{1} & \gate{\ket{0}} & \qw \\
+        \lstick{$c_{3,0}$}\setwiretype{n} &  &  &  &  &  &  &  &  & \setwiretype{c} & \cw & \cw \\
+        \lstick{$\ket{0}_{4}$} & \qw & \qw & \qw & \qw & \targ{} & \qw & \qw & \qw & \meter{} \vcw{1} & \gate{\ket{0}} & \qw \\
+        \lstick{$c_{4,0}$}\setwiretype{n} &  &  &  &  &  &  &  &  & \setwiretype{c} & \cw & \cw \\
+        \lstick{$\ket{0}_{5}$} & \qw & \qw & \qw & \qw & \qw & \targ{} & \qw & \qw & \meter{} \vcw{1} & \gate{\ket{0}} & \qw \\
+        \lstick{$c_{5,0}$}\setwiretype{n} &  &  &  &  &  &  &  &  & \setwiretype{c} & \cw & \cw \\
+        \lstick{$\ket{0}_{6}$} & \qw & \qw & \qw & \qw & \qw & \qw & \targ{} & \qw & \meter{} \vcw{1} & \gate{\ket{0}} & \qw \\
+        \lstick{$c_{6,0}$}\setwiretype{n} &  &  &  &  &  &  &  &  & \setwiretype{c} & \cw & \cw \\
+        \lstick{$\ket{0}_{7}$} & \qw & \qw & \qw & \qw & \qw & \qw & \qw & \targ{} & \meter{} \vcw{1} & \gate{\ket{0}} & \qw \\
+        \lstick{$c_{7,0}$}\setwiretype{n} &  &  &  &  &  &  &  &  & \setwiretype{c} & \cw & \cw \\
         \end{quantikz}
     "#]]
     .assert_eq(&tex);
@@ -369,8 +399,229 @@ fn quantikz_grouped_operation_should_unwrap_top_level() {
         }
     ";
 
-    let tex = quantikz(source, QuantikzGenerationOptions { group_by_scope: true }).expect("quantikz generation should succeed");
+    let options = QuantikzGenerationOptions { group_by_scope: true, ..Default::default() };
+    let tex = quantikz(source, options).expect("quantikz generation should succeed");
 
     assert!(tex.contains("PrepareBellState"), "Should contain inner operation name");
     assert!(!tex.contains("\\gate[wires=2]{Run}"), "Should not contain top-level wrapper");
 }
+
+#[test]
+fn quantikz_standalone_document() {
+    let tex = quantikz(
+        r"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Unit {
+                    use q = Qubit();
+                    H(q);
+                    M(q);
+                }
+            }
+        ",
+        QuantikzGenerationOptions { standalone: true, ..Default::default() }
+    ).expect("quantikz generation should succeed");
+
+    expect![[r#"
+        \documentclass[border=6pt]{standalone}
+        \usepackage{tikz}
+        \usetikzlibrary{quantikz2}
+        \begin{document}
+        \begin{quantikz}
+        \lstick{$\ket{0}_{0}$} & \gate{H} & \meter{} \vcw{1} & \qw \\
+        \lstick{$c_{0,0}$}\setwiretype{n} &  & \setwiretype{c} & \cw \\
+        \end{quantikz}
+        \end{document}
+    "#]]
+    .assert_eq(&tex);
+}
+
+#[test]
+fn quantikz_folded_with_spacing() {
+    let tex = quantikz(
+        r#"
+            namespace Test {
+                open Microsoft.Quantum.Math;
+                @EntryPoint()
+                operation Main() : Unit {
+                    use (q0, q1) = (Qubit(), Qubit());
+                    X(q0);
+                    X(q1);
+                    H(q0);
+                    CNOT(q0, q1);
+                    Rz(2.0 * PI() / 3.0, q1);
+                    H(q0);
+                    H(q1);
+                    M(q0);
+                    M(q1);
+                }
+            }
+        "#,
+        QuantikzGenerationOptions {
+            max_columns: Some(3),
+            row_sep: Some("0.6cm".into()),
+            column_sep: Some("0.3cm".into()),
+            ..Default::default()
+        }
+    ).expect("quantikz generation should succeed");
+
+    expect![[r#"
+        \begin{quantikz}[row sep={0.6cm}, column sep={0.3cm}]
+        \lstick{$\ket{0}_{0}$} & \gate{X} & \gate{H} & \ctrl{2} & \ \ldots\  \\
+        \lstick{$c_{0,0}$}\setwiretype{n} &  &  &  &  \\
+        \lstick{$\ket{0}_{1}$} & \gate{X} & \qw & \targ{} & \ \ldots\  \\
+        \lstick{$c_{1,0}$}\setwiretype{n} &  &  &  &  \\
+        \end{quantikz}
+        \par\bigskip
+        \begin{quantikz}[row sep={0.6cm}, column sep={0.3cm}]
+        \ \ldots\  & \gate{H} & \meter{} \vcw{1} & \qw & \qw \\
+        \setwiretype{n} &  & \setwiretype{c} & \cw & \cw \\
+        \ \ldots\  & \gate{R_z(2.0944)} & \gate{H} & \meter{} \vcw{1} & \qw \\
+        \setwiretype{n} &  &  & \setwiretype{c} & \cw \\
+        \end{quantikz}
+    "#]]
+    .assert_eq(&tex);
+}
+
+#[test]
+fn quantikz_folded_standalone_uses_varwidth() {
+    let tex = quantikz(
+        r"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Unit {
+                    use q = Qubit();
+                    H(q);
+                    X(q);
+                    M(q);
+                }
+            }
+        ",
+        QuantikzGenerationOptions { standalone: true, max_columns: Some(1), ..Default::default() }
+    ).expect("quantikz generation should succeed");
+
+    assert!(tex.starts_with("\\documentclass[border=6pt,varwidth]{standalone}"));
+    assert_eq!(tex.matches("\\begin{quantikz}").count(), 3);
+    assert!(tex.ends_with("\\end{document}\n"));
+}
+
+#[test]
+fn quantikz_classically_controlled_gate() {
+    let tex = quantikz(
+        r"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Result {
+                    use (q0, q1) = (Qubit(), Qubit());
+                    H(q0);
+                    if M(q0) == One {
+                        X(q1);
+                    }
+                    MResetZ(q1)
+                }
+            }
+        ",
+        QuantikzGenerationOptions::default()
+    ).expect("quantikz generation should succeed");
+
+    expect![[r#"
+        \begin{quantikz}
+        \lstick{$\ket{0}_{0}$} & \gate{H} & \meter{} \vcw{1} & \qw & \qw & \qw & \qw \\
+        \lstick{$c_{0,0}$}\setwiretype{n} &  & \setwiretype{c} & \control{} \vcw{1} & \cw & \cw & \cw \\
+        \lstick{$\ket{0}_{1}$} & \qw & \qw & \gate{X} & \meter{} \vcw{1} & \gate{\ket{0}} & \qw \\
+        \lstick{$c_{1,0}$}\setwiretype{n} &  &  &  & \setwiretype{c} & \cw & \cw \\
+        \end{quantikz}
+    "#]]
+    .assert_eq(&tex);
+}
+
+#[test]
+fn quantikz_max_depth_collapses_nested_groups() {
+    let source = r"
+        namespace Test {
+            operation PrepareBellState(q1 : Qubit, q2: Qubit) : Unit {
+                H(q1);
+                CNOT(q1, q2);
+            }
+
+            @EntryPoint()
+            operation Run() : Unit {
+                use (q1, q2) = (Qubit(), Qubit());
+                PrepareBellState(q1, q2);
+                ResetAll([q1, q2]);
+            }
+        }
+    ";
+
+    let options = QuantikzGenerationOptions {
+        circuit: Some(CircuitGenerationOptions { group_by_scope: true, max_depth: Some(1), ..Default::default() }),
+        ..Default::default()
+    };
+    let tex = quantikz(source, options).expect("quantikz generation should succeed");
+
+    assert!(tex.contains("\\gate[wires=2]{PrepareBellState}"));
+    assert!(!tex.contains("\\targ{}"), "Nested operations should be collapsed");
+}
+
+#[test]
+fn quantikz_simulated_run_follows_measurement_results() {
+    let source = r"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Result {
+                use q = Qubit();
+                X(q);
+                if M(q) == One {
+                    H(q);
+                } else {
+                    Y(q);
+                }
+                MResetZ(q)
+            }
+        }
+    ";
+
+    let options = QuantikzGenerationOptions {
+        circuit: Some(CircuitGenerationOptions {
+            generation_method: Some(CircuitGenerationMethod::Simulate),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let tex = quantikz(source, options).expect("quantikz generation should succeed");
+
+    assert!(tex.contains("\\gate{H}"));
+    assert!(!tex.contains("\\gate{Y}"));
+}
+
+#[test]
+fn quantikz_custom_labels_and_initial_states() {
+    let source = r"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Unit {
+                use (control, target_qubit) = (Qubit(), Qubit());
+                CNOT(control, target_qubit);
+                ResetAll([control, target_qubit]);
+            }
+        }
+    ";
+
+    let options = QuantikzGenerationOptions {
+        circuit: Some(CircuitGenerationOptions { source_locations: Some(true), ..Default::default() }),
+        variable_labels: true,
+        qubit_labels: Some(HashMap::from([(0, "\\mathrm{anc}".to_string())])),
+        initial_states: Some(HashMap::from([(0, "+".to_string())])),
+        ..Default::default()
+    };
+    let tex = quantikz(source, options).expect("quantikz generation should succeed");
+
+    // user labels take precedence, other wires use the variable names
+    expect![[r#"
+        \begin{quantikz}
+        \lstick{$\ket{+}_{\mathrm{anc}}$} & \ctrl{1} & \gate{\ket{0}} & \qw \\
+        \lstick{$\ket{0}_{target\_qubit}$} & \targ{} & \gate{\ket{0}} & \qw \\
+        \end{quantikz}
+    "#]]
+    .assert_eq(&tex);
+}
@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+
+use qsharp_bridge::readout::{ReadoutError, measurement_histogram, mitigate_readout, readout_calibration_matrix};
+use qsharp_bridge::sim::{ExecutionState, MeasurementOutcome, QsError};
+
+fn shot(outcomes: &[MeasurementOutcome]) -> ExecutionState {
+    ExecutionState {
+        outcomes: outcomes.to_vec(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_readout_error_new_invalid() {
+    assert!(ReadoutError::new(0.1, 0.2).is_ok());
+    assert!(ReadoutError::new(-0.1, 0.2).is_err());
+    assert!(ReadoutError::new(0.1, 1.2).is_err());
+}
+
+#[test]
+fn test_measurement_histogram() {
+    use MeasurementOutcome::{One, Zero};
+    let results = vec![shot(&[One, Zero]), shot(&[One, Zero]), shot(&[Zero, Zero]), shot(&[])];
+
+    let histogram = measurement_histogram(results);
+
+    assert_eq!(histogram, HashMap::from([("10".to_string(), 2), ("00".to_string(), 1)]));
+}
+
+#[test]
+fn test_measurement_histogram_skips_lost_shots() {
+    use MeasurementOutcome::{Loss, One, Zero};
+    let results = vec![shot(&[One, Loss]), shot(&[Zero, One])];
+
+    let histogram = measurement_histogram(results);
+
+    assert_eq!(histogram, HashMap::from([("01".to_string(), 1)]));
+}
+
+#[test]
+fn test_readout_calibration_matrix() {
+    let matrix = readout_calibration_matrix(vec![
+        ReadoutError::new(0.1, 0.2).unwrap(),
+        ReadoutError::new(0.0, 0.5).unwrap(),
+    ])
+    .unwrap();
+
+    assert_eq!(matrix.len(), 4);
+    // prepared `11`, read `01`: first bit flipped, second bit kept
+    assert!((matrix[0b01][0b11] - 0.2 * 0.5).abs() < 1e-12);
+    for column in 0..4 {
+        let sum: f64 = matrix.iter().map(|row| row[column]).sum();
+        assert!((sum - 1.0).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn test_mitigate_readout() {
+    let matrix = readout_calibration_matrix(vec![ReadoutError::new(0.1, 0.2).unwrap()]).unwrap();
+    // prepared 70% `0` and 30% `1`
+    let histogram = HashMap::from([("0".to_string(), 690), ("1".to_string(), 310)]);
+
+    let mitigated = mitigate_readout(histogram, matrix).unwrap();
+
+    assert!((mitigated["0"] - 0.7).abs() < 1e-9);
+    assert!((mitigated["1"] - 0.3).abs() < 1e-9);
+}
+
+#[test]
+fn test_mitigate_readout_invalid_input() {
+    let parameter = |error: QsError| match error {
+        QsError::InvalidParameter { parameter, .. } => parameter,
+        other => panic!("expected an invalid parameter error, got {other:?}"),
+    };
+    let matrix = readout_calibration_matrix(vec![ReadoutError::new(0.1, 0.2).unwrap()]).unwrap();
+
+    let error = mitigate_readout(HashMap::from([("01".to_string(), 1)]), matrix).unwrap_err();
+    assert_eq!(parameter(error), "histogram");
+
+    let error = mitigate_readout(HashMap::from([("0".to_string(), 1)]), vec![vec![0.5, 0.5], vec![0.5, 0.5]]).unwrap_err();
+    assert_eq!(parameter(error), "calibration_matrix");
+}
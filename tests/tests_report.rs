@@ -0,0 +1,125 @@
+use expect_test::expect;
+use qsharp_bridge::estimation_result::{EstimationResult, parse_estimation_results};
+use qsharp_bridge::report::{ReportFormat, estimate_report};
+use qsharp_bridge::sim::QsError;
+
+const SAMPLE_RESULT: &str = include_str!("assets/estimation_result.json");
+
+fn sample_result() -> EstimationResult {
+    parse_estimation_results(SAMPLE_RESULT).unwrap().remove(0)
+}
+
+#[test]
+fn report_as_markdown() {
+    let report = estimate_report(vec![sample_result()], ReportFormat::Markdown).unwrap();
+
+    expect![[r#"
+        # Resource estimates
+
+        ## Physical resource estimates
+
+        | Metric | qubit_gate_ns_e3 (surface_code) |
+        |---|---|
+        | Runtime | 5 microsecs |
+        | Physical qubits | 1200 |
+
+        ## Assumptions
+
+        - Uniform independent physical noise.
+    "#]]
+    .assert_eq(&report);
+}
+
+#[test]
+fn report_as_html_side_by_side() {
+    let mut other = sample_result();
+    other.qubit_model = "qubit_maj_ns_e4".to_string();
+    other.report_groups[0].entries[1].value = "<900>".to_string();
+
+    let report = estimate_report(vec![sample_result(), other], ReportFormat::Html).unwrap();
+
+    expect![[r#"
+        <h1>Resource estimates</h1>
+        <h2>Physical resource estimates</h2>
+        <table>
+        <thead>
+        <tr><th>Metric</th><th>qubit_gate_ns_e3 (surface_code)</th><th>qubit_maj_ns_e4 (surface_code)</th></tr>
+        </thead>
+        <tbody>
+        <tr><td>Runtime</td><td>5 microsecs</td><td>5 microsecs</td></tr>
+        <tr><td>Physical qubits</td><td>1200</td><td>&lt;900&gt;</td></tr>
+        </tbody>
+        </table>
+        <h2>Assumptions</h2>
+        <ul>
+        <li>Uniform independent physical noise.</li>
+        </ul>
+    "#]]
+    .assert_eq(&report);
+}
+
+#[test]
+fn report_as_plain_text_without_report_data() {
+    let mut result = sample_result();
+    result.report_groups.clear();
+    result.assumptions.clear();
+
+    let report = estimate_report(vec![result], ReportFormat::PlainText).unwrap();
+
+    expect![[r#"
+        Resource estimates
+        ==================
+
+        Physical resource estimates
+        ---------------------------
+        Metric           qubit_gate_ns_e3 (surface_code)
+        Runtime          5000 ns
+        rQOPS            4000000
+        Physical qubits  1200
+
+        Resource estimates breakdown
+        ----------------------------
+        Metric                       qubit_gate_ns_e3 (surface_code)
+        Logical algorithmic qubits   3
+        Algorithmic depth            10
+        Logical depth                12
+        Number of T states           4
+        Number of T factories        2
+        Physical algorithmic qubits  400
+        Physical T factory qubits    800
+
+        Logical qubit parameters
+        ------------------------
+        Metric                    qubit_gate_ns_e3 (surface_code)
+        QEC scheme                surface_code
+        Code distance             5
+        Physical qubits           100
+        Logical cycle time        500 ns
+        Logical qubit error rate  1e-6
+
+        T factory parameters
+        --------------------
+        Metric                             qubit_gate_ns_e3 (surface_code)
+        Physical qubits                    400
+        Runtime                            3000 ns
+        Number of output T states per run  1
+        Number of input T states per run   15
+        Distillation rounds                1
+        Distillation units                 15-to-1 space-efficient
+        Logical T state error rate         1e-5
+
+        Assumed error budget
+        --------------------
+        Metric                                qubit_gate_ns_e3 (surface_code)
+        Logical error probability             5e-4
+        T distillation error probability      5e-4
+        Rotation synthesis error probability  0
+    "#]]
+    .assert_eq(&report);
+}
+
+#[test]
+fn report_requires_results() {
+    let error = estimate_report(Vec::new(), ReportFormat::Markdown).unwrap_err();
+    assert!(matches!(error, QsError::InvalidParameter { parameter, .. } if parameter == "results"));
+}
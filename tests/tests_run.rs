@@ -1,6 +1,10 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use qsharp_bridge::sim::{run_qs, run_qs_with_options, ExecutionOptions};
+use qsharp_bridge::loss::loss_statistics;
+use qsharp_bridge::noise::{IdleNoise, Noise, NoiseModel, TwoQubitNoise};
+use qsharp_bridge::readout::ReadoutError;
+use qsharp_bridge::sim::{run_qs, run_qs_with_options, ExecutionOptions, MeasurementOutcome, QsError, Simulator};
 
 #[test]
 fn test_hello() {
@@ -119,4 +123,295 @@ fn test_no_loss() {
 
     assert_eq!(result.len(), 1);
     assert_eq!(result[0].result, Some("false".into()));
-}
\ No newline at end of file
+}
+#[test]
+fn test_noise_model_per_gate() {
+    let source = "operation Main() : Result { use q = Qubit(); X(q); H(q); H(q); MResetZ(q) }";
+    let noise_model = NoiseModel {
+        x: Some(Noise::BitFlip { p: 1.0 }),
+        ..Default::default()
+    };
+    let options = ExecutionOptions::from_shots(20).with_noise_model(noise_model);
+    let result = run_qs_with_options(source, options).unwrap();
+
+    assert_eq!(result.len(), 20);
+    for res in &result {
+        assert_eq!(res.result, Some("Zero".into()));
+    }
+}
+
+#[test]
+fn test_noise_model_correlated_two_qubit_noise() {
+    let source = std::fs::read_to_string("tests/assets/entanglement.qs").unwrap();
+    let noise_model = NoiseModel {
+        cx: Some(TwoQubitNoise::Correlated {
+            probabilities: HashMap::from([("IX".to_string(), 1.0)]),
+        }),
+        ..Default::default()
+    };
+    let options = ExecutionOptions::from_shots(20).with_noise_model(noise_model);
+    let result = run_qs_with_options(&source, options).unwrap();
+
+    for res in &result {
+        assert!(res.result == Some("(One, Zero)".into()) || res.result == Some("(Zero, One)".into()));
+    }
+}
+
+#[test]
+fn test_noise_model_falls_back_to_noise() {
+    let source = "operation Main() : Result { use q = Qubit(); X(q); MResetZ(q) }";
+    let noise_model = NoiseModel {
+        x: Some(Noise::Ideal),
+        ..Default::default()
+    };
+    let options = ExecutionOptions::new(20, Noise::BitFlip { p: 1.0 }, None).with_noise_model(noise_model);
+    let result = run_qs_with_options(source, options).unwrap();
+
+    // the measurement has no channel of its own, so the bit flip of `noise` applies before it
+    for res in &result {
+        assert_eq!(res.result, Some("Zero".into()));
+    }
+}
+
+#[test]
+fn test_readout_error() {
+    let source = "operation Main() : (Result, Result) { use (a, b) = (Qubit(), Qubit()); X(b); (MResetZ(a), MResetZ(b)) }";
+    let options = ExecutionOptions::from_shots(20).with_readout_error(ReadoutError::new(1.0, 1.0).unwrap());
+    let result = run_qs_with_options(source, options).unwrap();
+
+    for res in &result {
+        assert_eq!(res.result, Some("(One, Zero)".into()));
+    }
+}
+
+#[test]
+fn test_qubit_readout_error() {
+    let source = "operation Main() : (Result, Result) { use (a, b) = (Qubit(), Qubit()); (MResetZ(a), MResetZ(b)) }";
+    let options = ExecutionOptions::from_shots(20)
+        .with_readout_error(ReadoutError::new(1.0, 0.0).unwrap())
+        .with_qubit_readout_error(0, ReadoutError::new(0.0, 0.0).unwrap());
+    let result = run_qs_with_options(source, options).unwrap();
+
+    for res in &result {
+        assert_eq!(res.result, Some("(Zero, One)".into()));
+    }
+}
+
+#[test]
+fn test_qubit_noise() {
+    let source = "operation Main() : (Result, Result) { use (a, b) = (Qubit(), Qubit()); H(a); H(a); H(b); H(b); (MResetZ(a), MResetZ(b)) }";
+    let options = ExecutionOptions::from_shots(20).with_qubit_noise(1, Noise::BitFlip { p: 1.0 });
+    let result = run_qs_with_options(source, options).unwrap();
+
+    // `X` leaves |+> unchanged, the flips after the second H and before the measurement cancel out
+    for res in &result {
+        assert_eq!(res.result, Some("(Zero, Zero)".into()));
+        assert!(res.lost_qubits.is_empty());
+    }
+
+    let options = ExecutionOptions::from_shots(20).with_qubit_noise(1, Noise::PhaseFlip { p: 1.0 });
+    let result = run_qs_with_options(source, options).unwrap();
+    for res in &result {
+        assert_eq!(res.result, Some("(Zero, One)".into()));
+    }
+}
+
+#[test]
+fn test_qubit_loss_overrides() {
+    let source = "operation Main() : (Bool, Bool) { use (a, b) = (Qubit(), Qubit()); X(a); X(b); (IsLossResult(MResetZ(a)), IsLossResult(MResetZ(b))) }";
+    let options = ExecutionOptions::from_qubit_loss(1.0).with_qubit_loss(0, 0.0);
+    let result = run_qs_with_options(source, options).unwrap();
+
+    assert_eq!(result[0].result, Some("(false, true)".into()));
+    assert_eq!(result[0].lost_qubits, vec![1]);
+}
+
+#[test]
+fn test_loss_outcomes() {
+    let source = "operation Main() : Result[] { use qs = Qubit[3]; X(qs[2]); MResetEachZ(qs) }";
+    let options = ExecutionOptions::from_shots(5).with_qubit_loss(1, 1.0);
+    let result = run_qs_with_options(source, options).unwrap();
+
+    for res in &result {
+        assert_eq!(
+            res.outcomes,
+            vec![MeasurementOutcome::Zero, MeasurementOutcome::Loss, MeasurementOutcome::One]
+        );
+        assert_eq!(res.lost_qubits, vec![1]);
+    }
+    assert_eq!(loss_statistics(result).measurement_loss_rate, 1.0 / 3.0);
+}
+
+#[test]
+fn test_qubit_loss_invalid() {
+    let options = ExecutionOptions::from_shots(1).with_qubit_loss(0, 1.5);
+    let result = run_qs_with_options("operation Main() : Unit {}", options);
+    assert!(matches!(result, Err(QsError::InvalidParameter { .. })));
+}
+
+#[test]
+fn test_idle_noise() {
+    let source = "operation Main() : (Result, Result) { use (a, b) = (Qubit(), Qubit()); X(a); CNOT(a, b); (MResetZ(a), MResetZ(b)) }";
+    let idle_noise = IdleNoise {
+        noise: Noise::BitFlip { p: 1.0 },
+        loss: None,
+    };
+    let options = ExecutionOptions::from_shots(20).with_idle_noise(idle_noise);
+    let result = run_qs_with_options(source, options).unwrap();
+
+    // `b` is flipped while it waits for the X on `a`, the CNOT flips it back
+    for res in &result {
+        assert_eq!(res.result, Some("(One, Zero)".into()));
+    }
+}
+
+#[test]
+fn test_idle_noise_with_global_noise() {
+    let source = "operation Main() : Result { use (a, b) = (Qubit(), Qubit()); X(a); CZ(a, b); MResetZ(b) }";
+    let idle_noise = IdleNoise {
+        noise: Noise::BitFlip { p: 1.0 },
+        loss: None,
+    };
+    let shots = 2000;
+    let options = ExecutionOptions::new(shots, Noise::BitFlip { p: 0.1 }, None).with_idle_noise(idle_noise);
+    let result = run_qs_with_options(source, options).unwrap();
+
+    // the idle flip is not a gate, only the CZ and the measurement of `b` can flip it back:
+    // P(One) = 0.9^2 + 0.1^2 = 0.82, where a noisy idle flip would give 0.756
+    let ones = result.iter().filter(|res| res.result == Some("One".into())).count();
+    let rate = ones as f64 / shots as f64;
+    assert!((rate - 0.82).abs() < 0.03, "unexpected rate {rate}");
+}
+
+#[test]
+fn test_idle_noise_before_release() {
+    let source = "operation Main() : Result { use (a, b) = (Qubit(), Qubit()); H(a); H(a); MResetZ(a) }";
+    let idle_noise = IdleNoise {
+        noise: Noise::BitFlip { p: 0.0 },
+        loss: Some(1.0),
+    };
+    let options = ExecutionOptions::from_shots(5).with_idle_noise(idle_noise);
+    let result = run_qs_with_options(source, options).unwrap();
+
+    // `b` is never used, it waits for the three layers of `a` until it is released
+    for res in &result {
+        assert_eq!(res.result, Some("Zero".into()));
+        assert_eq!(res.lost_qubits, vec![1]);
+    }
+}
+
+#[test]
+fn test_idle_noise_rejects_non_pauli_noise() {
+    let idle_noise = IdleNoise {
+        noise: Noise::AmplitudeDamping { gamma: 0.1 },
+        loss: None,
+    };
+    let options = ExecutionOptions::from_shots(1).with_idle_noise(idle_noise);
+    let result = run_qs_with_options("operation Main() : Unit {}", options);
+    assert!(matches!(result, Err(QsError::InvalidParameter { .. })));
+}
+
+#[test]
+fn test_density_matrix_entanglement() {
+    let source = std::fs::read_to_string("tests/assets/entanglement.qs").unwrap();
+    let options = ExecutionOptions::from_shots(20).with_simulator(Simulator::DensityMatrix);
+    let result = run_qs_with_options(&source, options).unwrap();
+
+    assert_eq!(result.len(), 20);
+    for res in &result {
+        assert_eq!(res.qubit_count, 2);
+        assert_eq!(res.states.len(), 2);
+        assert!(res.result == Some("(One, One)".into()) || res.result == Some("(Zero, Zero)".into()));
+    }
+}
+
+#[test]
+fn test_density_matrix_amplitude_damping() {
+    let source = "operation Main() : Result { use q = Qubit(); X(q); MResetZ(q) }";
+    let noise_model = NoiseModel {
+        x: Some(Noise::AmplitudeDamping { gamma: 1.0 }),
+        ..Default::default()
+    };
+    let options = ExecutionOptions::from_shots(20)
+        .with_noise_model(noise_model)
+        .with_simulator(Simulator::DensityMatrix);
+    let result = run_qs_with_options(source, options).unwrap();
+
+    for res in &result {
+        assert_eq!(res.result, Some("Zero".into()));
+    }
+}
+
+#[test]
+fn test_density_matrix_rejects_non_pauli_noise_on_sparse_simulator() {
+    let source = "operation Main() : Result { use q = Qubit(); X(q); MResetZ(q) }";
+    let options = ExecutionOptions::from_noise(Noise::AmplitudeDamping { gamma: 0.5 });
+    assert!(run_qs_with_options(source, Arc::new(options)).is_err());
+}
+
+#[test]
+fn test_density_matrix_too_many_qubits() {
+    let source = "operation Main() : Result[] { use qs = Qubit[11]; MResetEachZ(qs) }";
+    let options = ExecutionOptions::from_shots(1).with_simulator(Simulator::DensityMatrix);
+    let error = run_qs_with_options(source, options).err().expect("more than 10 qubits should fail");
+    assert!(error.to_string().contains("at most 10 qubits"));
+}
+
+#[test]
+fn test_stabilizer_entanglement() {
+    let source = std::fs::read_to_string("tests/assets/entanglement.qs").unwrap();
+    let options = ExecutionOptions::from_shots(20).with_simulator(Simulator::Stabilizer);
+    let result = run_qs_with_options(&source, options).unwrap();
+
+    assert_eq!(result.len(), 20);
+    for res in &result {
+        assert!(res.result == Some("(One, One)".into()) || res.result == Some("(Zero, Zero)".into()));
+    }
+}
+
+#[test]
+fn test_stabilizer_large_ghz_state() {
+    let source = "operation Main() : Result[] {
+        use qs = Qubit[300];
+        H(qs[0]);
+        for i in 1..299 { CNOT(qs[i - 1], qs[i]); }
+        MResetEachZ(qs)
+    }";
+    let options = ExecutionOptions::from_shots(4).with_simulator(Simulator::Stabilizer);
+    let result = run_qs_with_options(source, options).unwrap();
+
+    for res in &result {
+        let outcomes = &res.outcomes;
+        assert_eq!(outcomes.len(), 300);
+        assert!(outcomes.iter().all(|outcome| *outcome == outcomes[0]));
+    }
+}
+
+#[test]
+fn test_stabilizer_pauli_noise() {
+    let source = "operation Main() : Result { use q = Qubit(); S(q); Z(q); MResetZ(q) }";
+    let options = ExecutionOptions::new(20, Noise::BitFlip { p: 1.0 }, None).with_simulator(Simulator::Stabilizer);
+    let result = run_qs_with_options(source, options).unwrap();
+
+    // a flip after each of the gates and one before the measurement
+    for res in &result {
+        assert_eq!(res.result, Some("One".into()));
+    }
+}
+
+#[test]
+fn test_stabilizer_rejects_non_clifford_gates() {
+    let source = "operation Main() : Result { use q = Qubit(); H(q); T(q); MResetZ(q) }";
+    let options = ExecutionOptions::from_shots(1).with_simulator(Simulator::Stabilizer);
+    let error = run_qs_with_options(source, options).err().expect("T is not a Clifford gate");
+    assert!(error.to_string().contains("`t` is not a Clifford gate"));
+
+    let source = "operation Main() : Result { use q = Qubit(); Rz(0.3, q); MResetZ(q) }";
+    let options = ExecutionOptions::from_shots(1).with_simulator(Simulator::Stabilizer);
+    assert!(run_qs_with_options(source, options).is_err());
+
+    let source = "operation Main() : Result { use q = Qubit(); Rx(Std.Math.PI(), q); MResetZ(q) }";
+    let options = ExecutionOptions::from_shots(1).with_simulator(Simulator::Stabilizer);
+    let result = run_qs_with_options(source, options).unwrap();
+    assert_eq!(result[0].result, Some("One".into()));
+}
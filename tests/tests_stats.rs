@@ -0,0 +1,85 @@
+use qsharp_bridge::circuit::CircuitGenerationOptions;
+use qsharp_bridge::stats::{circuit_stats, circuit_stats_operation};
+
+#[test]
+fn circuit_stats_counts_gates_and_depth() {
+    let source = r"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Result[] {
+                use qs = Qubit[3];
+                H(qs[0]);
+                T(qs[0]);
+                Adjoint T(qs[1]);
+                CNOT(qs[0], qs[1]);
+                CCNOT(qs[0], qs[1], qs[2]);
+                MResetEachZ(qs)
+            }
+        }
+    ";
+
+    let stats = circuit_stats(source, CircuitGenerationOptions::default()).expect("circuit stats should succeed");
+
+    assert_eq!(stats.width, 3);
+    assert_eq!(stats.gate_count, 5);
+    assert_eq!(stats.gate_counts.get("T"), Some(&2));
+    assert_eq!(stats.gate_counts.get("X"), Some(&2));
+    assert_eq!(stats.two_qubit_gate_count, 1);
+    assert_eq!(stats.t_count, 2);
+    assert_eq!(stats.measurement_count, 3);
+    // the measurements of `MResetEachZ` are followed by resets
+    assert_eq!(stats.qubit_depths, vec![6, 6, 6]);
+    assert_eq!(stats.depth, 6);
+}
+
+#[test]
+fn circuit_stats_flattens_groups() {
+    let source = r"
+        namespace Test {
+            operation PrepareBellState(q1 : Qubit, q2: Qubit) : Unit {
+                H(q1);
+                CNOT(q1, q2);
+            }
+
+            operation Run() : Unit {
+                use (q1, q2) = (Qubit(), Qubit());
+                PrepareBellState(q1, q2);
+                PrepareBellState(q1, q2);
+                ResetAll([q1, q2]);
+            }
+        }
+    ";
+
+    let grouped = circuit_stats_operation("Test.Run", source, CircuitGenerationOptions { group_by_scope: true, ..Default::default() })
+        .expect("circuit stats should succeed");
+    let flat = circuit_stats_operation("Test.Run", source, CircuitGenerationOptions::default())
+        .expect("circuit stats should succeed");
+
+    assert_eq!(grouped, flat);
+    assert_eq!(flat.gate_count, 4);
+    assert_eq!(flat.two_qubit_gate_count, 2);
+    assert_eq!(flat.gate_counts.get("PrepareBellState"), None);
+}
+
+#[test]
+fn circuit_stats_classically_controlled_gate_follows_its_measurement() {
+    let source = r"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Result {
+                use (q0, q1) = (Qubit(), Qubit());
+                H(q0);
+                if M(q0) == One {
+                    X(q1);
+                }
+                MResetZ(q1)
+            }
+        }
+    ";
+
+    let stats = circuit_stats(source, CircuitGenerationOptions::default()).expect("circuit stats should succeed");
+
+    // `X` waits for the measurement of `q0`, then `MResetZ` measures and resets `q1`
+    assert_eq!(stats.qubit_depths, vec![2, 5]);
+    assert_eq!(stats.depth, 5);
+}
@@ -0,0 +1,91 @@
+use qsharp_bridge::circuit::CircuitGenerationOptions;
+use qsharp_bridge::svg::{circuit_svg, circuit_svg_operation, SvgGenerationOptions};
+
+#[test]
+fn svg_one_gate() {
+    let svg = circuit_svg(
+        r"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Unit {
+                    use q = Qubit();
+                    H(q);
+                    M(q);
+                }
+            }
+        ",
+        SvgGenerationOptions::default()
+    ).expect("svg generation should succeed");
+
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.ends_with("</svg>\n"));
+    assert!(svg.contains(">q0</text>"), "Should label the qubit wire");
+    assert!(svg.contains(">H</text>"), "Should contain the H gate box");
+    assert!(svg.contains("<path d=\"M "), "Should contain a measurement meter");
+}
+
+#[test]
+fn svg_operation_toffoli() {
+    let source = r"
+        namespace Test {
+            operation Main() : Unit {
+                use q = Qubit[3];
+                CCNOT(q[0], q[1], q[2]);
+            }
+        }
+    ";
+
+    let svg = circuit_svg_operation("Test.Main", source, SvgGenerationOptions::default()).expect("svg generation should succeed");
+
+    assert_eq!(svg.matches(" r=\"4\" fill=\"black\"").count(), 2, "Should draw two control dots");
+    assert_eq!(svg.matches(" r=\"11\" fill=\"white\"").count(), 1, "Should draw one target");
+}
+
+#[test]
+fn svg_swap_gate() {
+    let svg = circuit_svg(
+        r"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Unit {
+                    use q = Qubit[2];
+                    SWAP(q[0], q[1]);
+                }
+            }
+        ",
+        SvgGenerationOptions::default()
+    ).expect("svg generation should succeed");
+
+    assert!(!svg.contains(">SWAP</text>"), "Swap should be drawn with crosses, not a box");
+}
+
+#[test]
+fn svg_grouped_operation() {
+    let source = r"
+        namespace Test {
+            operation PrepareBellState(q1 : Qubit, q2: Qubit) : Unit {
+                H(q1);
+                CNOT(q1, q2);
+            }
+
+            @EntryPoint()
+            operation Run() : Result {
+                use (q1, q2) = (Qubit(), Qubit());
+                PrepareBellState(q1, q2);
+                let r = M(q1);
+                Reset(q1);
+                Reset(q2);
+                return r;
+            }
+        }
+    ";
+
+    let options = SvgGenerationOptions {
+        circuit: CircuitGenerationOptions { group_by_scope: true, ..Default::default() },
+    };
+    let svg = circuit_svg(source, options).expect("svg generation should succeed");
+
+    assert!(svg.contains(">PrepareBellState</text>"), "Should label the group");
+    assert!(svg.contains("stroke-dasharray"), "Should outline the group");
+    assert!(svg.contains(">H</text>"), "Should draw the gates inside the group");
+}
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use qsharp_bridge::noise::Noise;
+use qsharp_bridge::readout::ReadoutError;
+use qsharp_bridge::sim::{ExecutionOptions, ExecutionState};
+use qsharp_bridge::sweep::{SuccessPredicate, noise_sweep};
+
+const SOURCE: &str = "operation Main() : Result { use q = Qubit(); X(q); H(q); H(q); MResetZ(q) }";
+const SEED: u64 = 42;
+
+struct Expect(&'static str);
+
+impl SuccessPredicate for Expect {
+    fn is_success(&self, shot: ExecutionState) -> bool {
+        shot.result.as_deref() == Some(self.0)
+    }
+}
+
+fn depolarizing(points: &[f64]) -> Vec<Noise> {
+    points.iter().map(|&p| Noise::Depolarizing { p }).collect()
+}
+
+#[test]
+fn test_noise_sweep() {
+    let points = noise_sweep(SOURCE, Arc::new(ExecutionOptions::from_shots(50)), depolarizing(&[0.0, 0.1, 0.5]), SEED, Box::new(Expect("One"))).unwrap();
+
+    assert_eq!(points.len(), 3);
+    assert_eq!(points[0].histogram, HashMap::from([("One".to_string(), 50)]));
+    assert_eq!(points[0].successes, 50);
+    assert_eq!(points[0].success_probability, 1.0);
+    for point in &points {
+        assert_eq!(point.histogram.values().sum::<u64>(), 50);
+        assert_eq!(point.success_probability, point.successes as f64 / 50.0);
+    }
+    assert_ne!(points[1].seed, points[2].seed);
+}
+
+#[test]
+fn test_noise_sweep_is_reproducible() {
+    let first = noise_sweep(SOURCE, Arc::new(ExecutionOptions::from_shots(50)), depolarizing(&[0.2, 0.4]), SEED, Box::new(Expect("One"))).unwrap();
+    let second = noise_sweep(SOURCE, Arc::new(ExecutionOptions::from_shots(50)), depolarizing(&[0.2, 0.4]), SEED, Box::new(Expect("One"))).unwrap();
+
+    for (first, second) in first.iter().zip(&second) {
+        assert_eq!(first.seed, second.seed);
+        assert_eq!(first.histogram, second.histogram);
+    }
+}
+
+#[test]
+fn test_noise_sweep_seed() {
+    let first = noise_sweep(SOURCE, Arc::new(ExecutionOptions::from_shots(10)), depolarizing(&[0.2]), SEED, Box::new(Expect("One"))).unwrap();
+    let second = noise_sweep(SOURCE, Arc::new(ExecutionOptions::from_shots(10)), depolarizing(&[0.2]), SEED + 1, Box::new(Expect("One"))).unwrap();
+
+    assert_ne!(first[0].seed, second[0].seed);
+}
+
+#[test]
+fn test_noise_sweep_keeps_base_options() {
+    let readout_error = ReadoutError {
+        p_one_given_zero: 0.0,
+        p_zero_given_one: 1.0,
+    };
+    let options = ExecutionOptions::from_shots(20).with_readout_error(readout_error);
+
+    let points = noise_sweep(SOURCE, options, depolarizing(&[0.0]), SEED, Box::new(Expect("One"))).unwrap();
+
+    // every `One` is read as `Zero`
+    assert_eq!(points[0].histogram, HashMap::from([("Zero".to_string(), 20)]));
+    assert_eq!(points[0].successes, 0);
+}
+
+#[test]
+fn test_noise_sweep_invalid_noise() {
+    let result = noise_sweep(SOURCE, Arc::new(ExecutionOptions::from_shots(10)), vec![Noise::BitFlip { p: 2.0 }], SEED, Box::new(Expect("One")));
+    assert!(result.is_err());
+}
@@ -0,0 +1,228 @@
+use expect_test::expect;
+use qsharp_bridge::text::{circuit_text, circuit_text_operation, TextGenerationOptions};
+
+#[test]
+fn text_one_gate() {
+    let text = circuit_text(
+        r"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Unit {
+                    use q = Qubit();
+                    H(q);
+                    M(q);
+                }
+            }
+        ",
+        TextGenerationOptions::default()
+    ).expect("text generation should succeed");
+
+    expect![[r#"
+               ┌─┐  ┌─┐
+        q0:   ─┤H├──┤M├──
+               └─┘  └╥┘
+                     ║
+        c0,0:        ╚═══
+
+    "#]]
+    .assert_eq(&text);
+}
+
+#[test]
+fn text_operation_toffoli() {
+    let source = r"
+        namespace Test {
+            operation Main() : Unit {
+                use q = Qubit[3];
+                CCNOT(q[0], q[1], q[2]);
+            }
+        }
+    ";
+
+    let text = circuit_text_operation("Test.Main", source, TextGenerationOptions::default()).expect("text generation should succeed");
+
+    expect![[r#"
+
+        q0: ─●──
+             │
+             │
+        q1: ─●──
+             │
+             │
+        q2: ─⊕──
+
+    "#]]
+    .assert_eq(&text);
+}
+
+#[test]
+fn text_swap_gate() {
+    let text = circuit_text(
+        r"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Unit {
+                    use q = Qubit[2];
+                    SWAP(q[0], q[1]);
+                }
+            }
+        ",
+        TextGenerationOptions::default()
+    ).expect("text generation should succeed");
+
+    expect![[r#"
+
+        q0: ─╳──
+             │
+             │
+        q1: ─╳──
+
+    "#]]
+    .assert_eq(&text);
+}
+
+const ROTATION_SOURCE: &str = r#"
+    namespace Test {
+        open Microsoft.Quantum.Math;
+        @EntryPoint()
+        operation Main() : Unit {
+            use (q0, q1) = (Qubit(), Qubit());
+            X(q0);
+            X(q1);
+            H(q0);
+            CNOT(q0, q1);
+            Rz(2.0 * PI() / 3.0, q1);
+            H(q0);
+            H(q1);
+            M(q0);
+            M(q1);
+        }
+    }
+"#;
+
+#[test]
+fn text_rotation_circuit() {
+    let text = circuit_text(ROTATION_SOURCE, TextGenerationOptions::default()).expect("text generation should succeed");
+
+    expect![[r#"
+               ┌─┐  ┌─┐         ┌─┐       ┌─┐
+        q0:   ─┤X├──┤H├──●──────┤H├───────┤M├───────
+               └─┘  └─┘  │      └─┘       └╥┘
+                         │                 ║
+        c0,0:            │                 ╚════════
+                         │
+               ┌─┐       │  ┌──────────┐  ┌─┐  ┌─┐
+        q1:   ─┤X├───────⊕──┤Rz(2.0944)├──┤H├──┤M├──
+               └─┘          └──────────┘  └─┘  └╥┘
+                                                ║
+        c1,0:                                   ╚═══
+
+    "#]]
+    .assert_eq(&text);
+}
+
+#[test]
+fn text_rotation_circuit_folded() {
+    let text = circuit_text(ROTATION_SOURCE, TextGenerationOptions { max_width: Some(30), ..Default::default() }).expect("text generation should succeed");
+
+    expect![[r#"
+               ┌─┐  ┌─┐
+        q0:   ─┤X├──┤H├──●─»
+               └─┘  └─┘  │
+                         │
+        c0,0:            │ »
+                         │
+               ┌─┐       │
+        q1:   ─┤X├───────⊕─»
+               └─┘
+
+        c1,0:              »
+
+
+                    ┌─┐       ┌─┐
+        q0:   «─────┤H├───────┤M├─»
+                    └─┘       └╥┘
+                               ║
+        c0,0: «                ╚══»
+
+                ┌──────────┐  ┌─┐
+        q1:   «─┤Rz(2.0944)├──┤H├─»
+                └──────────┘  └─┘
+
+        c1,0: «                   »
+
+
+
+        q0:   «──────
+
+
+        c0,0: «══════
+
+                ┌─┐
+        q1:   «─┤M├──
+                └╥┘
+                 ║
+        c1,0: «  ╚═══
+
+    "#]]
+    .assert_eq(&text);
+
+    assert!(text.lines().all(|line| line.chars().count() <= 30));
+}
+
+#[test]
+fn text_classically_controlled_gate() {
+    let text = circuit_text(
+        r"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Unit {
+                    use q = Qubit();
+                    if M(q) == One {
+                        X(q);
+                    }
+                }
+            }
+        ",
+        TextGenerationOptions::default()
+    ).expect("text generation should succeed");
+
+    // the result gets its own classical row, which controls the X
+    expect![[r#"
+               ┌─┐  ┌─┐
+        q0:   ─┤M├──┤X├──
+               └╥┘  └╥┘
+                ║    ║
+        c0,0:   ╚════■═══
+
+    "#]]
+    .assert_eq(&text);
+}
+
+#[test]
+fn text_gate_after_measurement() {
+    let text = circuit_text(
+        r"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Unit {
+                    use q = Qubit();
+                    M(q);
+                    H(q);
+                }
+            }
+        ",
+        TextGenerationOptions::default()
+    ).expect("text generation should succeed");
+
+    // the qubit stays quantum after its measurement
+    expect![[r#"
+               ┌─┐  ┌─┐
+        q0:   ─┤M├──┤H├──
+               └╥┘  └─┘
+                ║
+        c0,0:   ╚════════
+
+    "#]]
+    .assert_eq(&text);
+}